@@ -1,6 +1,6 @@
-CREATE DATABASE IF NOT EXISTS btc_db;
+CREATE DATABASE IF NOT EXISTS {database};
 
-CREATE TABLE IF NOT EXISTS btc_db.wallet_info (
+CREATE TABLE IF NOT EXISTS {database}.wallet_info (
     address String,
     balance String,
     nonce UInt64,
@@ -9,17 +9,26 @@ CREATE TABLE IF NOT EXISTS btc_db.wallet_info (
 ) ENGINE = ReplacingMergeTree()
 ORDER BY address;
 
-CREATE TABLE IF NOT EXISTS btc_db.transactions (
+CREATE TABLE IF NOT EXISTS {database}.transactions (
     hash String,
     block_number UInt64,
     from_addr String,
     to_addr String,
     value String,
-    sensivity UInt8
+    sensivity UInt8,
+    -- EVM only, always NULL here
+    category Nullable(UInt8),
+    status Nullable(UInt8)
 ) ENGINE = MergeTree()
 ORDER BY block_number;
 
-CREATE TABLE IF NOT EXISTS btc_db.owner_info (
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS category Nullable(UInt8) AFTER sensivity;
+
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS status Nullable(UInt8) AFTER category;
+
+CREATE TABLE IF NOT EXISTS {database}.owner_info (
     address String,
     person_name String,
     person_id String,
@@ -27,15 +36,146 @@ CREATE TABLE IF NOT EXISTS btc_db.owner_info (
 ) ENGINE = ReplacingMergeTree()
 ORDER BY address;
 
+---------------------------------------------------------
+-- ADDRESS TAGS
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.address_tags (
+    address String,
+    tag String,
+    created_at DateTime DEFAULT now()
+) ENGINE = MergeTree()
+ORDER BY (address, tag);
+
+---------------------------------------------------------
+-- TOKEN TRANSFERS
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.token_transfers (
+    tx_hash String,
+    block_number UInt64,
+    log_index UInt32,
+    token_address String,
+    from_addr String,
+    to_addr String,
+    amount String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, log_index);
+
+---------------------------------------------------------
+-- TOKEN BALANCE (no token delta pipeline on this chain yet)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.address_token_balance (
+    address String,
+    token_address String,
+    balance Int256
+) ENGINE = SummingMergeTree()
+ORDER BY (address, token_address);
+
+---------------------------------------------------------
+-- TOKEN METADATA
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.token_metadata (
+    token_address String,
+    name String,
+    symbol String,
+    decimals UInt8,
+    total_supply String,
+    is_verified UInt8,
+    created_at DateTime DEFAULT now(),
+    updated_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(updated_at)
+ORDER BY token_address;
+
+---------------------------------------------------------
+-- MONEY FLOWS
+-- cross_chain_exit: funds sent into a bridge contract, i.e. leaving the chain
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.money_flows (
+    tx_hash String,
+    from_addr String,
+    to_addr String,
+    amount String,
+    asset String,
+    cross_chain_exit Bool DEFAULT false,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, from_addr, to_addr, asset, amount);
+
+ALTER TABLE {database}.money_flows
+    ADD COLUMN IF NOT EXISTS cross_chain_exit Bool DEFAULT false AFTER asset;
+
+---------------------------------------------------------
+-- CONTRACT CALLS
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.contract_calls (
+    tx_hash String,
+    contract_address String,
+    method String,
+    data String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, contract_address, method);
+
 /*
 New sections
 */
+---------------------------------------------------------
+-- ADDRESS LOOKUP INDEXES
+-- transactions / token_transfers are not ordered by address,
+-- so address history queries rely on these skip indexes.
+-- They only cover parts written after they were added, existing data needs a
+-- one-off MATERIALIZE INDEX (README, Address index migration)
+---------------------------------------------------------
+ALTER TABLE {database}.transactions
+    ADD INDEX IF NOT EXISTS idx_from_addr from_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.transactions
+    ADD INDEX IF NOT EXISTS idx_to_addr to_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_from_addr from_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_to_addr to_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_block_number block_number TYPE minmax GRANULARITY 4;
+
+---------------------------------------------------------
+-- BLOCKS
+-- one row per fully indexed block, written after all of its rows (used by gap repair)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.blocks (
+    number UInt64,
+    hash String,
+    parent_hash String,
+    timestamp UInt64,
+    tx_count Nullable(UInt64),
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY number;
+
 ---------------------------------------------------------
 -- SYNC STATE
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS btc_db.sync_state (
+CREATE TABLE IF NOT EXISTS {database}.sync_state (
     chain String,
     last_synced_block UInt64,
     updated_at DateTime DEFAULT now()
 ) ENGINE = ReplacingMergeTree(updated_at)
-ORDER BY chain;
\ No newline at end of file
+ORDER BY chain;
+
+---------------------------------------------------------
+-- BACKFILL RANGES
+-- checkpoint of every shard of a sharded backfill, next_block > range_end once done
+-- plan tells apart the shard layouts of successive backfills
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.backfill_ranges (
+    chain String,
+    plan UInt64,
+    range_start UInt64,
+    range_end UInt64,
+    next_block UInt64,
+    updated_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(next_block)
+ORDER BY (chain, plan, range_start);
\ No newline at end of file
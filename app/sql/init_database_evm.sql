@@ -0,0 +1,357 @@
+CREATE DATABASE IF NOT EXISTS {database};
+
+---------------------------------------------------------
+-- WALLET INFO
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.wallet_info (
+    address String,
+    balance String,
+    nonce UInt64,
+    type String,
+    person_id String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY address;
+
+-- databases from before the shared EVM schema (bsc_db) lack inserted_at
+ALTER TABLE {database}.wallet_info
+    ADD COLUMN IF NOT EXISTS inserted_at DateTime DEFAULT now();
+
+---------------------------------------------------------
+-- TRANSACTIONS
+-- category: TxCategory code from the classifier (1 failed ... 10 other),
+-- status: receipt status (1 success, 0 reverted), NULL on rows from before they existed
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.transactions (
+    hash String,
+    block_number UInt64,
+    from_addr String,
+    to_addr String,
+    value String,
+    sensivity UInt8,
+    category Nullable(UInt8),
+    status Nullable(UInt8),
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (block_number, hash);
+
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS category Nullable(UInt8) AFTER sensivity;
+
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS status Nullable(UInt8) AFTER category;
+
+-- older bsc_db tables are a plain MergeTree ordered by block_number, which ALTER can't
+-- change (startup warns, README has the migration)
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS inserted_at DateTime DEFAULT now();
+
+---------------------------------------------------------
+-- OWNER INFO
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.owner_info (
+    address String,
+    person_name String,
+    person_id String,
+    personal_id UInt16,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY address;
+
+ALTER TABLE {database}.owner_info
+    ADD COLUMN IF NOT EXISTS inserted_at DateTime DEFAULT now();
+
+---------------------------------------------------------
+-- ADDRESS TAGS
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.address_tags (
+    address String,
+    tag String,
+    created_at DateTime DEFAULT now()
+) ENGINE = MergeTree()
+ORDER BY (address, tag);
+
+---------------------------------------------------------
+-- TOKEN TRANSFERS (CANONICAL TABLE)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.token_transfers (
+    tx_hash String,
+    block_number UInt64,
+    log_index UInt32,
+    token_address String,
+    from_addr String,
+    to_addr String,
+    amount String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, log_index);
+
+---------------------------------------------------------
+-- TOKEN DELTA (CANONICAL TABLE)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.address_token_delta (
+    tx_hash String,
+    log_index UInt32,
+    direction UInt8,     -- صفر یعنی خروج. یک یعنی ورود به حساب
+    address String,
+    token_address String,
+    delta Int256,
+    block_number UInt64,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, log_index, direction);
+
+---------------------------------------------------------
+-- MV: DELTA FROM (SENDER)
+---------------------------------------------------------
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_token_delta_from
+TO {database}.address_token_delta
+AS
+SELECT
+    tx_hash,
+    log_index,
+    0 AS direction,
+    from_addr AS address,
+    token_address,
+    -toInt256(amount) AS delta,
+    block_number
+FROM {database}.token_transfers
+WHERE from_addr != '0x0000000000000000000000000000000000000000';
+
+---------------------------------------------------------
+-- MV: DELTA TO (RECEIVER)
+---------------------------------------------------------
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_token_delta_to
+TO {database}.address_token_delta
+AS
+SELECT
+    tx_hash,
+    log_index,
+    1 AS direction,
+    to_addr AS address,
+    token_address,
+    toInt256(amount) AS delta,
+    block_number
+FROM {database}.token_transfers
+WHERE to_addr != '0x0000000000000000000000000000000000000000';
+
+---------------------------------------------------------
+-- FINAL TOKEN BALANCE TABLE
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.address_token_balance (
+    address String,
+    token_address String,
+    balance Int256
+) ENGINE = SummingMergeTree()
+ORDER BY (address, token_address);
+
+---------------------------------------------------------
+-- MV: FINAL BALANCE
+---------------------------------------------------------
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_token_balance
+TO {database}.address_token_balance
+AS
+SELECT
+    address,
+    token_address,
+    delta AS balance
+FROM {database}.address_token_delta;
+
+---------------------------------------------------------
+-- TOKEN META DETAILS (Importent)
+---------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS {database}.token_metadata (
+    token_address String,
+    name String,
+    symbol String,
+    decimals UInt8,
+    total_supply String,
+    is_verified UInt8,
+    created_at DateTime DEFAULT now(),
+    updated_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(updated_at)
+ORDER BY token_address;
+
+---------------------------------------------------------
+-- MONEY FLOWS
+-- cross_chain_exit: funds sent into a bridge contract, i.e. leaving the chain
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.money_flows (
+    tx_hash String,
+    from_addr String,
+    to_addr String,
+    amount String,
+    asset String,
+    cross_chain_exit Bool DEFAULT false,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, from_addr, to_addr, asset, amount);
+
+ALTER TABLE {database}.money_flows
+    ADD COLUMN IF NOT EXISTS cross_chain_exit Bool DEFAULT false AFTER asset;
+
+---------------------------------------------------------
+-- CONTRACT CALLS
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.contract_calls (
+    tx_hash String,
+    contract_address String,
+    method String,
+    data String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (tx_hash, contract_address, method);
+
+---------------------------------------------------------
+-- DEX SWAPS
+-- net assets the tx sender paid (assets_in) and got (assets_out) in a swap, one row per
+-- swap, raw amounts at the same index as their asset, native coin as its symbol
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.dex_swaps (
+    tx_hash String,
+    block_number UInt64,
+    user_addr String,
+    assets_in Array(String),
+    amounts_in Array(String),
+    assets_out Array(String),
+    amounts_out Array(String),
+    router String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (block_number, tx_hash);
+
+ALTER TABLE {database}.dex_swaps
+    ADD INDEX IF NOT EXISTS idx_user_addr user_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+---------------------------------------------------------
+-- LIQUIDITY EVENTS
+-- LP tokens minted to (add) or burned by (remove) the tx sender, token0 / token1 in
+-- address order so the events of a pool can be summed into a position
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.liquidity_events (
+    tx_hash String,
+    block_number UInt64,
+    user_addr String,
+    action LowCardinality(String),
+    pool String,
+    token0 String,
+    token1 String,
+    amount0 String,
+    amount1 String,
+    lp_amount String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (block_number, tx_hash, pool);
+
+ALTER TABLE {database}.liquidity_events
+    ADD INDEX IF NOT EXISTS idx_user_addr user_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+---------------------------------------------------------
+-- BRIDGE TRANSFERS
+-- funds the tx sender moved into a bridge contract: one listed in the chain's `bridges`
+-- registry (bridge_name set) or one emitting a known bridge event (event_sig set)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.bridge_transfers (
+    tx_hash String,
+    block_number UInt64,
+    user_addr String,
+    asset String,
+    amount String,
+    bridge_contract String,
+    bridge_name String,
+    event_sig String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (block_number, tx_hash);
+
+ALTER TABLE {database}.bridge_transfers
+    ADD INDEX IF NOT EXISTS idx_user_addr user_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+---------------------------------------------------------
+-- DEFI POSITIONS
+-- ledger of the tx sender's stakes, deposits and withdraws: protocol is the contract
+-- called, asset the underlying (token address or native symbol), receipt_token the
+-- token minted or burned for it (empty when none)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.defi_positions (
+    tx_hash String,
+    block_number UInt64,
+    user_addr String,
+    action LowCardinality(String),
+    protocol String,
+    asset String,
+    amount String,
+    receipt_token String,
+    receipt_amount String,
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY (block_number, tx_hash);
+
+ALTER TABLE {database}.defi_positions
+    ADD INDEX IF NOT EXISTS idx_user_addr user_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+---------------------------------------------------------
+-- ADDRESS LOOKUP INDEXES
+-- transactions / token_transfers are not ordered by address,
+-- so address history queries rely on these skip indexes.
+-- They only cover parts written after they were added, existing data needs a
+-- one-off MATERIALIZE INDEX (README, Address index migration)
+---------------------------------------------------------
+ALTER TABLE {database}.transactions
+    ADD INDEX IF NOT EXISTS idx_from_addr from_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.transactions
+    ADD INDEX IF NOT EXISTS idx_to_addr to_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_from_addr from_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_to_addr to_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_block_number block_number TYPE minmax GRANULARITY 4;
+
+---------------------------------------------------------
+-- BLOCKS
+-- one row per fully indexed block, written after all of its rows: hash / parent_hash
+-- for reorg detection, tx_count for gap repair (NULL on rows from before it existed)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.blocks (
+    number UInt64,
+    hash String,
+    parent_hash String,
+    timestamp UInt64,
+    tx_count Nullable(UInt64),
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY number;
+
+ALTER TABLE {database}.blocks
+    ADD COLUMN IF NOT EXISTS tx_count Nullable(UInt64) AFTER timestamp;
+
+---------------------------------------------------------
+-- SYNC STATE
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.sync_state (
+    chain String,
+    last_synced_block UInt64,
+    updated_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(updated_at)
+ORDER BY chain;
+
+---------------------------------------------------------
+-- BACKFILL RANGES
+-- checkpoint of every shard of a sharded backfill, next_block > range_end once done
+-- plan tells apart the shard layouts of successive backfills
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.backfill_ranges (
+    chain String,
+    plan UInt64,
+    range_start UInt64,
+    range_end UInt64,
+    next_block UInt64,
+    updated_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(next_block)
+ORDER BY (chain, plan, range_start);
\ No newline at end of file
@@ -1,9 +1,9 @@
-CREATE DATABASE IF NOT EXISTS tron_db;
+CREATE DATABASE IF NOT EXISTS {database};
 
 ---------------------------------------------------------
 -- SYNC STATE 
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.sync_state (
+CREATE TABLE IF NOT EXISTS {database}.sync_state (
     chain String,
     last_synced_block UInt64,
     updated_at DateTime DEFAULT now()
@@ -11,25 +11,63 @@ CREATE TABLE IF NOT EXISTS tron_db.sync_state (
 ENGINE = ReplacingMergeTree(updated_at)
 ORDER BY chain;
 
+---------------------------------------------------------
+-- BLOCKS
+-- one row per fully indexed block, written after all of its rows (used by gap repair)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.blocks (
+    number UInt64,
+    hash String,
+    parent_hash String,
+    timestamp UInt64,
+    tx_count Nullable(UInt64),
+    inserted_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(inserted_at)
+ORDER BY number;
+
+---------------------------------------------------------
+-- BACKFILL RANGES
+-- checkpoint of every shard of a sharded backfill, next_block > range_end once done
+-- plan tells apart the shard layouts of successive backfills
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.backfill_ranges (
+    chain String,
+    plan UInt64,
+    range_start UInt64,
+    range_end UInt64,
+    next_block UInt64,
+    updated_at DateTime DEFAULT now()
+) ENGINE = ReplacingMergeTree(next_block)
+ORDER BY (chain, plan, range_start);
+
 ---------------------------------------------------------
 -- TRANSACTIONS
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.transactions (
+CREATE TABLE IF NOT EXISTS {database}.transactions (
     hash String,
     block_number UInt64,
     from_addr String,
     to_addr String,
     value String,
     sensivity UInt8,
+    -- EVM only, always NULL here
+    category Nullable(UInt8),
+    status Nullable(UInt8),
     inserted_at DateTime DEFAULT now()
 )
 ENGINE = ReplacingMergeTree(inserted_at)
 ORDER BY (block_number, hash);
 
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS category Nullable(UInt8) AFTER sensivity;
+
+ALTER TABLE {database}.transactions
+    ADD COLUMN IF NOT EXISTS status Nullable(UInt8) AFTER category;
+
 ---------------------------------------------------------
 -- TOKEN TRANSFERS
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.token_transfers (
+CREATE TABLE IF NOT EXISTS {database}.token_transfers (
     tx_hash String,
     block_number UInt64,
     log_index UInt32,
@@ -42,10 +80,32 @@ CREATE TABLE IF NOT EXISTS tron_db.token_transfers (
 ENGINE = ReplacingMergeTree(inserted_at)
 ORDER BY (tx_hash, log_index);
 
+---------------------------------------------------------
+-- ADDRESS LOOKUP INDEXES
+-- transactions / token_transfers are not ordered by address,
+-- so address history queries rely on these skip indexes.
+-- They only cover parts written after they were added, existing data needs a
+-- one-off MATERIALIZE INDEX (README, Address index migration)
+---------------------------------------------------------
+ALTER TABLE {database}.transactions
+    ADD INDEX IF NOT EXISTS idx_from_addr from_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.transactions
+    ADD INDEX IF NOT EXISTS idx_to_addr to_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_from_addr from_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_to_addr to_addr TYPE bloom_filter(0.01) GRANULARITY 4;
+
+ALTER TABLE {database}.token_transfers
+    ADD INDEX IF NOT EXISTS idx_block_number block_number TYPE minmax GRANULARITY 4;
+
 ---------------------------------------------------------
 -- WALLET INFO 
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.wallet_info (
+CREATE TABLE IF NOT EXISTS {database}.wallet_info (
     address String,
     balance String,
     nonce UInt64,
@@ -59,7 +119,7 @@ ORDER BY address;
 ---------------------------------------------------------
 -- OWNER INFO 
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.owner_info (
+CREATE TABLE IF NOT EXISTS {database}.owner_info (
     address String,
     person_name String,
     person_id String,
@@ -72,7 +132,7 @@ ORDER BY address;
 ---------------------------------------------------------
 -- ADDRESS TAGS 
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.address_tags (
+CREATE TABLE IF NOT EXISTS {database}.address_tags (
     address String,
     tag String,
     created_at DateTime DEFAULT now()
@@ -83,7 +143,7 @@ ORDER BY (address, tag);
 ---------------------------------------------------------
 -- TOKEN METADATA 
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.token_metadata (
+CREATE TABLE IF NOT EXISTS {database}.token_metadata (
     token_address String,
     name String,
     symbol String,
@@ -96,10 +156,24 @@ CREATE TABLE IF NOT EXISTS tron_db.token_metadata (
 ENGINE = ReplacingMergeTree(updated_at)
 ORDER BY token_address;
 
+---------------------------------------------------------
+-- TOKEN BALANCE (no token delta pipeline on this chain yet)
+---------------------------------------------------------
+CREATE TABLE IF NOT EXISTS {database}.address_token_balance (
+    address String,
+    token_address String,
+    balance Int256
+)
+ENGINE = SummingMergeTree()
+ORDER BY (address, token_address);
+
 ---------------------------------------------------------
 -- AML TABLES (ADD ONLY)
 ---------------------------------------------------------
-CREATE TABLE IF NOT EXISTS tron_db.contract_calls (
+-- the columns before `method` are the original layout, kept so databases created with it
+-- take the same inserts (the indexer writes tx_hash, contract_address, method and data).
+-- Those databases keep their original sorting key, see README (Tron schema migration).
+CREATE TABLE IF NOT EXISTS {database}.contract_calls (
     tx_hash String,
     block_number UInt64,
     owner_address String,
@@ -108,12 +182,16 @@ CREATE TABLE IF NOT EXISTS tron_db.contract_calls (
     selector FixedString(8),
     data String,
     success UInt8,
+    method String,
     inserted_at DateTime DEFAULT now()
 )
 ENGINE = ReplacingMergeTree(inserted_at)
-ORDER BY (block_number, tx_hash);
+ORDER BY (tx_hash, contract_address, method);
+
+ALTER TABLE {database}.contract_calls
+    ADD COLUMN IF NOT EXISTS method String AFTER success;
 
-CREATE TABLE IF NOT EXISTS tron_db.token_balance_snapshot (
+CREATE TABLE IF NOT EXISTS {database}.token_balance_snapshot (
     tx_hash String,
     block_number UInt64,
     address String,
@@ -125,7 +203,8 @@ CREATE TABLE IF NOT EXISTS tron_db.token_balance_snapshot (
 ENGINE = ReplacingMergeTree(inserted_at)
 ORDER BY (block_number, tx_hash, address);
 
-CREATE TABLE IF NOT EXISTS tron_db.money_flows (
+-- block_number ... action are the original layout, kept like in contract_calls
+CREATE TABLE IF NOT EXISTS {database}.money_flows (
     tx_hash String,
     block_number UInt64,
     from_address String,
@@ -140,7 +219,23 @@ CREATE TABLE IF NOT EXISTS tron_db.money_flows (
         'MIXER' = 5,
         'UNKNOWN' = 99
     ),
+    from_addr String,
+    to_addr String,
+    asset String,
+    cross_chain_exit Bool DEFAULT false,
     inserted_at DateTime DEFAULT now()
 )
 ENGINE = ReplacingMergeTree(inserted_at)
-ORDER BY (block_number, tx_hash);
\ No newline at end of file
+ORDER BY (tx_hash, from_addr, to_addr, asset, amount);
+
+ALTER TABLE {database}.money_flows
+    ADD COLUMN IF NOT EXISTS from_addr String AFTER action;
+
+ALTER TABLE {database}.money_flows
+    ADD COLUMN IF NOT EXISTS to_addr String AFTER from_addr;
+
+ALTER TABLE {database}.money_flows
+    ADD COLUMN IF NOT EXISTS asset String AFTER to_addr;
+
+ALTER TABLE {database}.money_flows
+    ADD COLUMN IF NOT EXISTS cross_chain_exit Bool DEFAULT false AFTER asset;
\ No newline at end of file
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use clickhouse::Client;
+use serde::Deserialize;
+
+use crate::models::{AddressActivityRow, OwnerRow, TokenBalanceRow, WalletRow};
+
+pub async fn get_wallet(
+    client: &Client,
+    address: &str,
+) -> anyhow::Result<Option<WalletRow>> {
+    let row = client
+        .query(
+            "SELECT ?fields
+             FROM wallet_info FINAL
+             WHERE address = ?
+             LIMIT 1",
+        )
+        .bind(address)
+        .fetch_optional::<WalletRow>()
+        .await?;
+
+    Ok(row)
+}
+
+pub async fn get_owner(
+    client: &Client,
+    address: &str,
+) -> anyhow::Result<Option<OwnerRow>> {
+    let row = client
+        .query(
+            "SELECT ?fields
+             FROM owner_info FINAL
+             WHERE address = ?
+             LIMIT 1",
+        )
+        .bind(address)
+        .fetch_optional::<OwnerRow>()
+        .await?;
+
+    Ok(row)
+}
+
+pub async fn get_tags(
+    client: &Client,
+    address: &str,
+) -> anyhow::Result<Vec<String>> {
+    let tags = client
+        .query(
+            "SELECT DISTINCT tag
+             FROM address_tags
+             WHERE address = ?
+             ORDER BY tag",
+        )
+        .bind(address)
+        .fetch_all::<String>()
+        .await?;
+
+    Ok(tags)
+}
+
+pub async fn count_token_balances(
+    client: &Client,
+    address: &str,
+) -> anyhow::Result<u64> {
+    let total = client
+        .query(
+            "SELECT count()
+             FROM (
+                 SELECT token_address
+                 FROM address_token_balance
+                 WHERE address = ?
+                 GROUP BY token_address
+                 HAVING sum(balance) != 0
+             )",
+        )
+        .bind(address)
+        .fetch_one::<u64>()
+        .await?;
+
+    Ok(total)
+}
+
+// non-zero balances only, ordered by token address so offsets stay stable
+pub async fn get_token_balances(
+    client: &Client,
+    address: &str,
+    limit: u64,
+    offset: u64,
+) -> anyhow::Result<Vec<TokenBalanceRow>> {
+    let rows = client
+        .query(
+            "SELECT
+                 b.token_address AS token_address,
+                 toString(b.balance) AS balance,
+                 ifNull(m.symbol, '') AS symbol,
+                 ifNull(m.decimals, 0) AS decimals
+             FROM (
+                 SELECT token_address, sum(balance) AS balance
+                 FROM address_token_balance
+                 WHERE address = ?
+                 GROUP BY token_address
+                 HAVING balance != 0
+             ) AS b
+             LEFT JOIN (
+                 SELECT token_address, symbol, decimals
+                 FROM token_metadata FINAL
+             ) AS m ON m.token_address = b.token_address
+             ORDER BY token_address
+             LIMIT ? OFFSET ?",
+        )
+        .bind(address)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all::<TokenBalanceRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    In,
+    Out,
+    Any,
+}
+
+#[derive(Debug, Clone)]
+pub enum AssetFilter {
+    Any,
+    Native,
+    Token(String),
+}
+
+// position of the last row of the previous page
+#[derive(Debug, Clone)]
+pub struct ActivityCursor {
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub seq: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityFilter {
+    pub direction: Direction,
+    pub asset: AssetFilter,
+    pub from_block: u64,
+    pub to_block: u64,
+    // TxCategory code / receipt status of the transaction, EVM only
+    pub category: Option<u8>,
+    pub status: Option<u8>,
+    pub cursor: Option<ActivityCursor>,
+}
+
+impl ActivityFilter {
+    // conditions on the `transactions` row of each activity
+    fn tx_conditions(&self) -> String {
+        let mut conds = String::new();
+        if self.category.is_some() {
+            conds.push_str(" AND category = ?");
+        }
+        if self.status.is_some() {
+            conds.push_str(" AND status = ?");
+        }
+        conds
+    }
+}
+
+// native txs and token transfers touching `address`, newest first
+pub async fn get_address_activity(
+    client: &Client,
+    address: &str,
+    filter: &ActivityFilter,
+    limit: u64,
+) -> anyhow::Result<Vec<AddressActivityRow>> {
+    let address_cond = match filter.direction {
+        Direction::In => "to_addr = ?",
+        Direction::Out => "from_addr = ?",
+        Direction::Any => "(from_addr = ? OR to_addr = ?)",
+    };
+
+    let tx_conds = filter.tx_conditions();
+
+    let native = format!(
+        "SELECT 'native' AS kind, hash AS tx_hash, block_number, toUInt32(0) AS seq,
+                from_addr, to_addr, '' AS asset, value AS amount
+         FROM transactions
+         WHERE {} AND block_number BETWEEN ? AND ?{}",
+        address_cond, tx_conds
+    );
+
+    // token transfers carry no category, so they are matched through their transaction
+    let token_tx_cond = if tx_conds.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " AND tx_hash IN (SELECT hash FROM transactions WHERE block_number BETWEEN ? AND ?{})",
+            tx_conds
+        )
+    };
+
+    let token = format!(
+        "SELECT 'token' AS kind, tx_hash, block_number, toUInt32(log_index + 1) AS seq,
+                from_addr, to_addr, token_address AS asset, amount
+         FROM token_transfers
+         WHERE {} AND block_number BETWEEN ? AND ?{}{}",
+        address_cond,
+        if matches!(filter.asset, AssetFilter::Token(_)) { " AND token_address = ?" } else { "" },
+        token_tx_cond
+    );
+
+    let source = match filter.asset {
+        AssetFilter::Any => format!("{} UNION ALL {}", native, token),
+        AssetFilter::Native => native,
+        AssetFilter::Token(_) => token,
+    };
+
+    let cursor_cond = if filter.cursor.is_some() {
+        "WHERE (block_number, tx_hash, seq) < (?, ?, ?)"
+    } else {
+        ""
+    };
+
+    let sql = format!(
+        "SELECT ?fields
+         FROM ({})
+         {}
+         ORDER BY block_number DESC, tx_hash DESC, seq DESC
+         LIMIT 1 BY tx_hash, seq
+         LIMIT ?",
+        source, cursor_cond
+    );
+
+    let mut query = client.query(&sql);
+
+    // bind in the same order the placeholders were written
+    let branches: &[&str] = match filter.asset {
+        AssetFilter::Any => &["native", "token"],
+        AssetFilter::Native => &["native"],
+        AssetFilter::Token(_) => &["token"],
+    };
+    for &branch in branches {
+        query = query.bind(address);
+        if let Direction::Any = filter.direction {
+            query = query.bind(address);
+        }
+        query = query.bind(filter.from_block).bind(filter.to_block);
+        if branch == "token" {
+            if let AssetFilter::Token(token_address) = &filter.asset {
+                query = query.bind(token_address.as_str());
+            }
+            if !tx_conds.is_empty() {
+                query = query.bind(filter.from_block).bind(filter.to_block);
+            }
+        }
+        if let Some(category) = filter.category {
+            query = query.bind(category);
+        }
+        if let Some(status) = filter.status {
+            query = query.bind(status);
+        }
+    }
+
+    if let Some(cursor) = &filter.cursor {
+        query = query
+            .bind(cursor.block_number)
+            .bind(cursor.tx_hash.as_str())
+            .bind(cursor.seq);
+    }
+
+    let rows = query
+        .bind(limit)
+        .fetch_all::<AddressActivityRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+#[derive(Debug, Deserialize, clickhouse::Row)]
+struct TxLabelRow {
+    hash: String,
+    category: Option<u8>,
+    status: Option<u8>,
+}
+
+/// Category and receipt status of the transactions `hashes`, all within `from_block..=to_block`.
+pub async fn get_tx_labels(
+    client: &Client,
+    hashes: &[&str],
+    from_block: u64,
+    to_block: u64,
+) -> anyhow::Result<HashMap<String, (Option<u8>, Option<u8>)>> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows = client
+        .query(
+            "SELECT hash, category, status
+             FROM transactions
+             WHERE block_number BETWEEN ? AND ? AND hash IN ?
+             LIMIT 1 BY hash",
+        )
+        .bind(from_block)
+        .bind(to_block)
+        .bind(hashes)
+        .fetch_all::<TxLabelRow>()
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.hash, (row.category, row.status)))
+        .collect())
+}
@@ -0,0 +1,24 @@
+use clickhouse::Client;
+
+use crate::models::BackfillRangeRow;
+
+/// Shards of the chain's latest backfill plan with their checkpoints, ordered by range_start.
+pub async fn get_latest_backfill_plan(
+    client: &Client,
+    chain: &str,
+) -> anyhow::Result<Vec<BackfillRangeRow>> {
+    let rows = client
+        .query(
+            "SELECT ?fields
+             FROM backfill_ranges FINAL
+             WHERE chain = ?
+               AND plan = (SELECT max(plan) FROM backfill_ranges WHERE chain = ?)
+             ORDER BY range_start",
+        )
+        .bind(chain)
+        .bind(chain)
+        .fetch_all::<BackfillRangeRow>()
+        .await?;
+
+    Ok(rows)
+}
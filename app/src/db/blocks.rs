@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use clickhouse::Client;
+use serde::Deserialize;
+
+use crate::models::TransactionRow;
+
+pub async fn get_block_transactions(
+    client: &Client,
+    block_number: u64,
+) -> anyhow::Result<Vec<TransactionRow>> {
+    let rows = client
+        .query(
+            "SELECT ?fields
+             FROM transactions
+             WHERE block_number = ?
+             ORDER BY hash
+             LIMIT 1 BY hash",
+        )
+        .bind(block_number)
+        .fetch_all::<TransactionRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+pub async fn count_block_token_transfers(
+    client: &Client,
+    block_number: u64,
+) -> anyhow::Result<u64> {
+    let count = client
+        .query(
+            "SELECT count(DISTINCT tx_hash, log_index)
+             FROM token_transfers
+             WHERE block_number = ?",
+        )
+        .bind(block_number)
+        .fetch_one::<u64>()
+        .await?;
+
+    Ok(count)
+}
+
+/// Stored hash of an indexed block.
+pub async fn get_block_hash(
+    client: &Client,
+    block_number: u64,
+) -> anyhow::Result<Option<String>> {
+    let hash = client
+        .query(
+            "SELECT hash
+             FROM blocks FINAL
+             WHERE number = ?",
+        )
+        .bind(block_number)
+        .fetch_optional::<String>()
+        .await?;
+
+    Ok(hash)
+}
+
+/// Lowest block with a `blocks` record; blocks below it were indexed before records were kept.
+pub async fn get_first_recorded_block(client: &Client) -> anyhow::Result<Option<u64>> {
+    let first = client
+        .query("SELECT number FROM blocks ORDER BY number LIMIT 1")
+        .fetch_optional::<u64>()
+        .await?;
+
+    Ok(first)
+}
+
+#[derive(Debug, Deserialize, clickhouse::Row)]
+struct BlockTxCount {
+    number: u64,
+    tx_count: Option<u64>,
+}
+
+/// Blocks in `from_block..=to_block` with a `blocks` record, and the tx count it recorded.
+pub async fn get_recorded_tx_counts(
+    client: &Client,
+    from_block: u64,
+    to_block: u64,
+) -> anyhow::Result<HashMap<u64, Option<u64>>> {
+    let rows = client
+        .query(
+            "SELECT number, tx_count
+             FROM blocks FINAL
+             WHERE number BETWEEN ? AND ?",
+        )
+        .bind(from_block)
+        .bind(to_block)
+        .fetch_all::<BlockTxCount>()
+        .await?;
+
+    Ok(rows.into_iter().map(|row| (row.number, row.tx_count)).collect())
+}
+
+#[derive(Debug, Deserialize, clickhouse::Row)]
+struct StoredTxCount {
+    block_number: u64,
+    tx_count: u64,
+}
+
+/// Distinct transactions stored per block in `from_block..=to_block` (blocks without any are absent).
+/// Rows are deduplicated on (block_number, hash) first: unmerged duplicates of a
+/// ReplacingMergeTree, and BTC's plain MergeTree (which has no FINAL), count once.
+pub async fn get_stored_tx_counts(
+    client: &Client,
+    from_block: u64,
+    to_block: u64,
+) -> anyhow::Result<HashMap<u64, u64>> {
+    let rows = client
+        .query(
+            "SELECT block_number, count() AS tx_count
+             FROM (
+                 SELECT DISTINCT block_number, hash
+                 FROM transactions
+                 WHERE block_number BETWEEN ? AND ?
+             )
+             GROUP BY block_number",
+        )
+        .bind(from_block)
+        .bind(to_block)
+        .fetch_all::<StoredTxCount>()
+        .await?;
+
+    Ok(rows.into_iter().map(|row| (row.block_number, row.tx_count)).collect())
+}
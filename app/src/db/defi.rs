@@ -0,0 +1,51 @@
+use clickhouse::Client;
+
+use crate::models::OpenDefiPositionRow;
+
+// stakes and deposits minus withdraws of a raw amount column (stored as a string)
+fn net_sum(column: &str) -> String {
+    format!(
+        "sum(if(action = 'withdraw', -toInt256({0}), toInt256({0})))",
+        column
+    )
+}
+
+/// Positions `address` still holds per protocol and underlying asset, most recently
+/// touched first. A position with a receipt token is open while some of it is left,
+/// one without while more went in than came out (withdraws include the yield).
+pub async fn get_open_defi_positions(
+    client: &Client,
+    address: &str,
+) -> anyhow::Result<Vec<OpenDefiPositionRow>> {
+    // columns renamed in the subquery so the aliases don't shadow what they aggregate
+    let sql = format!(
+        "SELECT protocol,
+                asset,
+                toString({amount}) AS amount,
+                anyIf(rt, rt != '') AS receipt_token,
+                toString({receipt}) AS receipt_amount,
+                countIf(action != 'withdraw') AS entries,
+                countIf(action = 'withdraw') AS exits,
+                min(block) AS first_block,
+                max(block) AS last_block
+         FROM (
+             SELECT protocol, asset, action, block_number AS block, receipt_token AS rt,
+                    amount AS amt, receipt_amount AS ramt
+             FROM defi_positions FINAL
+             WHERE user_addr = ?
+         )
+         GROUP BY protocol, asset
+         HAVING if(countIf(rt != '') > 0, {receipt} > 0, {amount} > 0)
+         ORDER BY last_block DESC, protocol, asset",
+        amount = net_sum("amt"),
+        receipt = net_sum("ramt"),
+    );
+
+    let rows = client
+        .query(&sql)
+        .bind(address)
+        .fetch_all::<OpenDefiPositionRow>()
+        .await?;
+
+    Ok(rows)
+}
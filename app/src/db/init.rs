@@ -1,16 +1,70 @@
 use clickhouse::Client;
 
+/// Statements of an init script: `--` comment lines are dropped before splitting on `;`,
+/// so a comment can't cut a statement in two.
+fn statements(sql: &str) -> Vec<String> {
+    let code: Vec<&str> = sql
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("--"))
+        .collect();
+
+    code.join("\n")
+        .split(';')
+        .map(str::trim)
+        .filter(|stmt| !stmt.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 pub async fn run_sql(
     client: &Client,
     sql: &str,
 ) -> anyhow::Result<()> {
-    
-    for stmt in sql.split(';') {
-        let stmt = stmt.trim();
-        if !stmt.is_empty() {
-            // println!("Inja reside");
-            client.query(stmt).execute().await?;
-        }
+    for stmt in statements(sql) {
+        client.query(&stmt).execute().await?;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Fills the `{database}` placeholder of an init template; the name is validated
+/// as a plain identifier when the config is loaded.
+pub fn render_sql(template: &str, database: &str) -> String {
+    template.replace("{database}", database)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_with_semicolons_do_not_split_statements() {
+        let sql = "-- a; b\nCREATE TABLE t (\n    -- c; d\n    x UInt8\n);\n\nALTER TABLE t ADD COLUMN y UInt8;\n";
+        assert_eq!(
+            statements(sql),
+            vec!["CREATE TABLE t (\n    x UInt8\n)", "ALTER TABLE t ADD COLUMN y UInt8"]
+        );
+    }
+
+    #[test]
+    fn shipped_schemas_have_no_empty_or_comment_statements() {
+        for template in [
+            include_str!("../../sql/init_database_evm.sql"),
+            include_str!("../../sql/init_database_btc.sql"),
+            include_str!("../../sql/init_database_tron.sql"),
+        ] {
+            for stmt in statements(&render_sql(template, "test_db")) {
+                // block comments are left to ClickHouse, it accepts them before a statement
+                let code = match stmt.strip_prefix("/*") {
+                    Some(rest) => rest.split_once("*/").map_or("", |(_, code)| code),
+                    None => &stmt,
+                };
+                let head = code.split_whitespace().next().unwrap_or_default();
+                assert!(
+                    ["CREATE", "ALTER", "INSERT"].contains(&head),
+                    "unexpected statement start: {}",
+                    stmt
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use clickhouse::Client;
-use crate::db::init::run_sql;
+use crate::db::init::{render_sql, run_sql};
 
-pub async fn init_btc_db(client: &Client) -> anyhow::Result<()> {
-    let sql = include_str!("../../sql/init_database_btc.sql");
-    run_sql(client, sql).await
+pub async fn init_btc_db(client: &Client, database: &str) -> anyhow::Result<()> {
+    let sql = render_sql(include_str!("../../sql/init_database_btc.sql"), database);
+    run_sql(client, &sql).await
 }
@@ -0,0 +1,30 @@
+use clickhouse::Client;
+use crate::db::init::{render_sql, run_sql};
+
+// one schema for every EVM chain, each in its own database
+pub async fn init_evm_db(client: &Client, database: &str) -> anyhow::Result<()> {
+    let sql = render_sql(include_str!("../../sql/init_database_evm.sql"), database);
+    run_sql(client, &sql).await?;
+
+    // databases from before the shared schema (bsc_db) keep their plain MergeTree
+    // transactions table, which neither deduplicates re-indexed rows nor sorts by hash
+    let legacy = client
+        .query(
+            "SELECT engine
+             FROM system.tables
+             WHERE database = ?
+               AND name = 'transactions'
+               AND engine != 'ReplacingMergeTree'",
+        )
+        .bind(database)
+        .fetch_optional::<String>()
+        .await?;
+
+    if let Some(engine) = legacy {
+        println!(
+            "[EVM] {}.transactions is still a {}: re-indexed rows are not deduplicated, see README (EVM schema migration)",
+            database, engine
+        );
+    }
+    Ok(())
+}
@@ -1,7 +1,32 @@
 use clickhouse::Client;
-use crate::db::init::run_sql;
+use crate::db::init::{render_sql, run_sql};
 
-pub async fn init_tron_db(client: &Client) -> anyhow::Result<()> {
-    let sql = include_str!("../../sql/init_database_tron.sql");
-    run_sql(client, sql).await
-}
\ No newline at end of file
+// sorting key of contract_calls / money_flows in databases created before they held one
+// row per call / flow; ALTER can't change it, so several rows of a tx collapse into one
+const LEGACY_SORTING_KEY: &str = "block_number, tx_hash";
+
+pub async fn init_tron_db(client: &Client, database: &str) -> anyhow::Result<()> {
+    let sql = render_sql(include_str!("../../sql/init_database_tron.sql"), database);
+    run_sql(client, &sql).await?;
+
+    let legacy = client
+        .query(
+            "SELECT name
+             FROM system.tables
+             WHERE database = ?
+               AND name IN ('contract_calls', 'money_flows')
+               AND sorting_key = ?",
+        )
+        .bind(database)
+        .bind(LEGACY_SORTING_KEY)
+        .fetch_all::<String>()
+        .await?;
+
+    for table in legacy {
+        println!(
+            "[TRON] {}.{} still has its original sorting key ({}): only one row per tx survives merges, see README (Tron schema migration)",
+            database, table, LEGACY_SORTING_KEY
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use clickhouse::Client;
+
+use crate::models::{LiquidityEventRow, LiquidityPositionRow};
+
+// adds minus removes of a raw amount column (stored as a string)
+fn signed_sum(column: &str, alias: &str) -> String {
+    format!(
+        "toString(sum(if(action = 'add', toInt256({0}), -toInt256({0})))) AS {1}",
+        column, alias
+    )
+}
+
+/// Every pool `address` added liquidity to or removed it from, adds minus removes,
+/// most recently touched first.
+pub async fn get_liquidity_positions(
+    client: &Client,
+    address: &str,
+) -> anyhow::Result<Vec<LiquidityPositionRow>> {
+    // columns renamed in the subquery so the aliases don't shadow what they aggregate
+    let sql = format!(
+        "SELECT pool,
+                any(t0) AS token0,
+                any(t1) AS token1,
+                {},
+                {},
+                {},
+                countIf(action = 'add') AS adds,
+                countIf(action = 'remove') AS removes,
+                min(block) AS first_block,
+                max(block) AS last_block
+         FROM (
+             SELECT pool, action, block_number AS block, token0 AS t0, token1 AS t1,
+                    lp_amount AS lp, amount0 AS a0, amount1 AS a1
+             FROM liquidity_events FINAL
+             WHERE user_addr = ?
+         )
+         GROUP BY pool
+         ORDER BY last_block DESC, pool",
+        signed_sum("lp", "lp_amount"),
+        signed_sum("a0", "amount0"),
+        signed_sum("a1", "amount1"),
+    );
+
+    let rows = client
+        .query(&sql)
+        .bind(address)
+        .fetch_all::<LiquidityPositionRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+/// The latest `limit` liquidity events of `address`, newest first.
+pub async fn get_liquidity_events(
+    client: &Client,
+    address: &str,
+    limit: u64,
+) -> anyhow::Result<Vec<LiquidityEventRow>> {
+    let rows = client
+        .query(
+            "SELECT ?fields
+             FROM liquidity_events FINAL
+             WHERE user_addr = ?
+             ORDER BY block_number DESC, tx_hash DESC
+             LIMIT ?",
+        )
+        .bind(address)
+        .bind(limit)
+        .fetch_all::<LiquidityEventRow>()
+        .await?;
+
+    Ok(rows)
+}
@@ -1,6 +1,12 @@
+pub mod addresses;
+pub mod backfill;
+pub mod blocks;
+pub mod defi;
 pub mod init;
-pub mod init_eth;
 pub mod init_btc;
-pub mod init_bsc;
+pub mod init_evm;
 pub mod init_tron;
-pub mod sync_state;
\ No newline at end of file
+pub mod liquidity;
+pub mod reorg;
+pub mod sync_state;
+pub mod transactions;
\ No newline at end of file
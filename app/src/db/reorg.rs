@@ -0,0 +1,150 @@
+use clickhouse::Client;
+
+use crate::db::sync_state::update_last_synced_block;
+
+// block-numbered tables only the EVM schema has
+const EVM_TABLES: &[&str] = &["dex_swaps", "liquidity_events", "bridge_transfers", "defi_positions"];
+
+// (address, token) pairs whose balance the range's transfers changed; read from
+// token_transfers, which is deleted last, so a retried rollback finds them again
+const AFFECTED_BALANCES: &str = "SELECT from_addr, token_address FROM token_transfers
+                                 WHERE block_number BETWEEN ? AND ?
+                                 UNION DISTINCT
+                                 SELECT to_addr, token_address FROM token_transfers
+                                 WHERE block_number BETWEEN ? AND ?";
+
+/// Removes everything indexed for blocks `from_block..=to_block`, so they can be
+/// indexed again without duplicates. `evm` is set for the EVM schema, which derives
+/// token balances from transfers and has the decoded DeFi tables.
+///
+/// Every step can run again after a failure: tables are cleared in an order that keeps
+/// what the later steps select on (transactions, token_transfers) until the end.
+pub async fn delete_block_range(
+    client: &Client,
+    from_block: u64,
+    to_block: u64,
+    evm: bool,
+) -> anyhow::Result<()> {
+    if evm {
+        client
+            .query("DELETE FROM address_token_delta WHERE block_number BETWEEN ? AND ?")
+            .bind(from_block)
+            .bind(to_block)
+            .execute()
+            .await?;
+
+        // address_token_balance is a SummingMergeTree fed by insert-only MVs; the affected
+        // balances are rebuilt from the deltas that are left instead of being cancelled with
+        // negating rows, which a retry would apply twice
+        client
+            .query(&format!(
+                "DELETE FROM address_token_balance
+                 WHERE (address, token_address) IN ({})",
+                AFFECTED_BALANCES
+            ))
+            .bind(from_block)
+            .bind(to_block)
+            .bind(from_block)
+            .bind(to_block)
+            .execute()
+            .await?;
+
+        client
+            .query(&format!(
+                "INSERT INTO address_token_balance (address, token_address, balance)
+                 SELECT address, token_address, sum(delta)
+                 FROM address_token_delta FINAL
+                 WHERE (address, token_address) IN ({})
+                 GROUP BY address, token_address",
+                AFFECTED_BALANCES
+            ))
+            .bind(from_block)
+            .bind(to_block)
+            .bind(from_block)
+            .bind(to_block)
+            .execute()
+            .await?;
+
+        for table in EVM_TABLES {
+            client
+                .query(&format!("DELETE FROM {} WHERE block_number BETWEEN ? AND ?", table))
+                .bind(from_block)
+                .bind(to_block)
+                .execute()
+                .await?;
+        }
+    }
+
+    // money_flows / contract_calls carry no block number, so go through the tx hashes
+    for table in ["money_flows", "contract_calls"] {
+        client
+            .query(&format!(
+                "DELETE FROM {}
+                 WHERE tx_hash IN (
+                     SELECT hash FROM transactions WHERE block_number BETWEEN ? AND ?
+                 )",
+                table
+            ))
+            .bind(from_block)
+            .bind(to_block)
+            .execute()
+            .await?;
+    }
+
+    // wallet_info holds one snapshot per address with no block number, taken when the
+    // address was last seen; those of the range's addresses are dropped and saved again
+    // when their blocks are re-indexed (addresses only seen on an orphaned branch stay
+    // without one until they show up again)
+    client
+        .query(
+            "DELETE FROM wallet_info
+             WHERE address IN (
+                 SELECT from_addr FROM transactions WHERE block_number BETWEEN ? AND ?
+                 UNION DISTINCT
+                 SELECT to_addr FROM transactions WHERE block_number BETWEEN ? AND ?
+                 UNION DISTINCT
+                 SELECT from_addr FROM token_transfers WHERE block_number BETWEEN ? AND ?
+                 UNION DISTINCT
+                 SELECT to_addr FROM token_transfers WHERE block_number BETWEEN ? AND ?
+             )",
+        )
+        .bind(from_block)
+        .bind(to_block)
+        .bind(from_block)
+        .bind(to_block)
+        .bind(from_block)
+        .bind(to_block)
+        .bind(from_block)
+        .bind(to_block)
+        .execute()
+        .await?;
+
+    for (table, column) in [
+        ("token_transfers", "block_number"),
+        ("transactions", "block_number"),
+        ("blocks", "number"),
+    ] {
+        client
+            .query(&format!("DELETE FROM {} WHERE {} BETWEEN ? AND ?", table, column))
+            .bind(from_block)
+            .bind(to_block)
+            .execute()
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Removes everything indexed from `from_block` onwards so the canonical branch
+/// can be re-indexed, and rewinds sync_state to the block before it.
+pub async fn rollback_from_block(
+    client: &Client,
+    chain: &str,
+    from_block: u64,
+) -> anyhow::Result<()> {
+    delete_block_range(client, from_block, u64::MAX, true).await?;
+
+    update_last_synced_block(client, chain, from_block.saturating_sub(1)).await?;
+
+    Ok(())
+}
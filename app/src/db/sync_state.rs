@@ -1,6 +1,8 @@
 use clickhouse::Client;
 use serde::{Deserialize, Serialize};
 
+use crate::services::metrics;
+
 #[derive(Debug, Serialize, Deserialize, clickhouse::Row)]
 pub struct SyncStateRow {
     pub chain: String,
@@ -14,7 +16,7 @@ pub async fn get_last_synced_block(
     let row = client
         .query(
             "SELECT chain, last_synced_block
-             FROM sync_state
+             FROM sync_state FINAL
              WHERE chain = ?
              LIMIT 1",
         )
@@ -30,15 +32,16 @@ pub async fn update_last_synced_block(
     chain: &str,
     block: u64,
 ) -> anyhow::Result<()> {
-    client
+    let insert = client
         .query(
             "INSERT INTO sync_state (chain, last_synced_block)
              VALUES (?, ?)",
         )
         .bind(chain)
         .bind(block)
-        .execute()
-        .await?;
+        .execute();
+
+    metrics::insert("sync_state", insert).await?;
 
     Ok(())
 }
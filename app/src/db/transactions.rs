@@ -0,0 +1,74 @@
+use clickhouse::Client;
+
+use crate::models::{ContractCallRow, MoneyFlowRow, TokenTransferRow, TransactionRow};
+
+pub async fn get_transaction(
+    client: &Client,
+    hash: &str,
+) -> anyhow::Result<Option<TransactionRow>> {
+    let row = client
+        .query(
+            "SELECT ?fields
+             FROM transactions
+             WHERE hash = ?
+             LIMIT 1",
+        )
+        .bind(hash)
+        .fetch_optional::<TransactionRow>()
+        .await?;
+
+    Ok(row)
+}
+
+pub async fn get_token_transfers_by_tx(
+    client: &Client,
+    hash: &str,
+) -> anyhow::Result<Vec<TokenTransferRow>> {
+    let rows = client
+        .query(
+            "SELECT ?fields
+             FROM token_transfers
+             WHERE tx_hash = ?
+             ORDER BY log_index
+             LIMIT 1 BY log_index",
+        )
+        .bind(hash)
+        .fetch_all::<TokenTransferRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+pub async fn get_money_flows_by_tx(
+    client: &Client,
+    hash: &str,
+) -> anyhow::Result<Vec<MoneyFlowRow>> {
+    let rows = client
+        .query(
+            "SELECT ?fields
+             FROM money_flows
+             WHERE tx_hash = ?",
+        )
+        .bind(hash)
+        .fetch_all::<MoneyFlowRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+pub async fn get_contract_calls_by_tx(
+    client: &Client,
+    hash: &str,
+) -> anyhow::Result<Vec<ContractCallRow>> {
+    let rows = client
+        .query(
+            "SELECT ?fields
+             FROM contract_calls
+             WHERE tx_hash = ?",
+        )
+        .bind(hash)
+        .fetch_all::<ContractCallRow>()
+        .await?;
+
+    Ok(rows)
+}
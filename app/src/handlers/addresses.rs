@@ -0,0 +1,489 @@
+use axum::extract::{Path, Query, State};
+use axum::response::Json;
+use serde::{Deserialize, Serialize};
+
+use ethers::utils::hex;
+
+use crate::db::addresses::{
+    ActivityCursor,
+    ActivityFilter,
+    AssetFilter,
+    Direction,
+    count_token_balances,
+    get_address_activity,
+    get_owner,
+    get_tags,
+    get_token_balances,
+    get_tx_labels,
+    get_wallet,
+};
+use crate::config::ChainConfig;
+use crate::db::defi::get_open_defi_positions;
+use crate::db::liquidity::{get_liquidity_events, get_liquidity_positions};
+use crate::handlers::error::ApiError;
+use crate::helper::units::format_units;
+use crate::models::{AddressActivityRow, LiquidityEventRow, OpenDefiPositionRow, OwnerRow, WalletRow};
+use crate::services::ethereum_detail::TxCategory;
+use crate::state::AppState;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Deserialize)]
+pub struct ProfileParams {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct TokenBalance {
+    pub token_address: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub raw_balance: String,
+    pub balance: String,
+}
+
+#[derive(Serialize)]
+pub struct TokenBalancePage {
+    pub items: Vec<TokenBalance>,
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+#[derive(Serialize)]
+pub struct AddressProfile {
+    pub chain: String,
+    pub address: String,
+    pub wallet: Option<WalletRow>,
+    pub owner: Option<OwnerRow>,
+    pub tags: Vec<String>,
+    pub token_balances: TokenBalancePage,
+    // staked / lent funds still held by protocols; EVM only, empty elsewhere
+    pub defi_positions: Vec<OpenDefiPositionRow>,
+}
+
+// EVM addresses are stored as lowercase hex; BTC / Tron addresses are case-sensitive
+pub(crate) fn normalize_address(chain: &ChainConfig, address: &str) -> String {
+    let address = address.trim();
+
+    if chain.is_evm() {
+        address.to_lowercase()
+    } else {
+        address.to_string()
+    }
+}
+
+// GET /v1/{chain}/address/{addr}
+pub async fn get_address(
+    State(state): State<AppState>,
+    Path((chain, address)): Path<(String, String)>,
+    Query(params): Query<ProfileParams>,
+) -> Result<Json<AddressProfile>, ApiError> {
+    let client = state
+        .client(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+    let chain_config = state
+        .chain(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+
+    let address = normalize_address(chain_config, &address);
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = params.offset.unwrap_or(0);
+
+    let (wallet, owner, tags, total, balances, defi_positions) = tokio::try_join!(
+        get_wallet(&client, &address),
+        get_owner(&client, &address),
+        get_tags(&client, &address),
+        count_token_balances(&client, &address),
+        get_token_balances(&client, &address, limit, offset),
+        async {
+            // only the EVM schema has the table
+            if chain_config.is_evm() {
+                get_open_defi_positions(&client, &address).await
+            } else {
+                Ok(vec![])
+            }
+        },
+    )?;
+
+    if wallet.is_none()
+        && owner.is_none()
+        && tags.is_empty()
+        && total == 0
+        && defi_positions.is_empty()
+    {
+        return Err(ApiError::NotFound(format!("address {}", address)));
+    }
+
+    let items = balances
+        .into_iter()
+        .map(|row| TokenBalance {
+            balance: format_units(&row.balance, row.decimals),
+            token_address: row.token_address,
+            symbol: row.symbol,
+            decimals: row.decimals,
+            raw_balance: row.balance,
+        })
+        .collect();
+
+    Ok(Json(AddressProfile {
+        chain,
+        address,
+        wallet,
+        owner,
+        tags,
+        token_balances: TokenBalancePage {
+            items,
+            total,
+            limit,
+            offset,
+        },
+        defi_positions,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct HistoryParams {
+    pub direction: Option<String>,
+    pub asset: Option<String>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub category: Option<String>,
+    pub status: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct AddressActivity {
+    pub kind: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub log_index: Option<u32>,
+    pub direction: &'static str,
+    pub from_addr: String,
+    pub to_addr: String,
+    pub asset: String,
+    pub amount: String,
+    // of the transaction, EVM only
+    pub category: Option<&'static str>,
+    pub status: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+pub struct AddressHistoryPage {
+    pub chain: String,
+    pub address: String,
+    pub items: Vec<AddressActivity>,
+    pub next_cursor: Option<String>,
+}
+
+/// API name of a stored receipt status.
+pub(crate) fn status_name(status: Option<u8>) -> Option<&'static str> {
+    match status? {
+        1 => Some("success"),
+        _ => Some("failed"),
+    }
+}
+
+pub(crate) fn category_name(category: Option<u8>) -> Option<&'static str> {
+    category.and_then(TxCategory::from_code).map(TxCategory::name)
+}
+
+// cursors are hex("block:seq:hash") so clients treat them as opaque tokens
+fn encode_cursor(row: &AddressActivityRow) -> String {
+    hex::encode(format!("{}:{}:{}", row.block_number, row.seq, row.tx_hash))
+}
+
+fn decode_cursor(cursor: &str) -> Option<ActivityCursor> {
+    let raw = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+    let mut parts = raw.splitn(3, ':');
+
+    let block_number = parts.next()?.parse().ok()?;
+    let seq = parts.next()?.parse().ok()?;
+    let tx_hash = parts.next().filter(|hash| !hash.is_empty())?.to_string();
+    Some(ActivityCursor {
+        block_number,
+        seq,
+        tx_hash,
+    })
+}
+
+fn parse_cursor(cursor: &str) -> Result<ActivityCursor, ApiError> {
+    decode_cursor(cursor).ok_or_else(|| ApiError::BadRequest("invalid cursor".into()))
+}
+
+// GET /v1/{chain}/address/{addr}/transactions
+pub async fn get_address_transactions(
+    State(state): State<AppState>,
+    Path((chain, address)): Path<(String, String)>,
+    Query(params): Query<HistoryParams>,
+) -> Result<Json<AddressHistoryPage>, ApiError> {
+    let client = state
+        .client(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+    let chain_config = state
+        .chain(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+
+    let address = normalize_address(chain_config, &address);
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let direction = match params.direction.as_deref() {
+        None | Some("any") => Direction::Any,
+        Some("in") => Direction::In,
+        Some("out") => Direction::Out,
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "direction must be one of in, out, any (got {})",
+                other
+            )));
+        }
+    };
+
+    let asset = match params.asset.as_deref() {
+        None | Some("any") => AssetFilter::Any,
+        Some("native") => AssetFilter::Native,
+        Some(token) => AssetFilter::Token(normalize_address(chain_config, token)),
+    };
+
+    if (params.category.is_some() || params.status.is_some()) && !chain_config.is_evm() {
+        return Err(ApiError::BadRequest(
+            "category and status filters are only supported on EVM chains".into(),
+        ));
+    }
+
+    let category = match params.category.as_deref() {
+        None | Some("any") => None,
+        Some(name) => Some(TxCategory::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = TxCategory::ALL.iter().map(|c| c.name()).collect();
+            ApiError::BadRequest(format!(
+                "category must be one of {}, any (got {})",
+                names.join(", "),
+                name
+            ))
+        })? as u8),
+    };
+
+    let status = match params.status.as_deref() {
+        None | Some("any") => None,
+        Some("success") => Some(1),
+        Some("failed") => Some(0),
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "status must be one of success, failed, any (got {})",
+                other
+            )));
+        }
+    };
+
+    let cursor = params.cursor.as_deref().map(parse_cursor).transpose()?;
+
+    let filter = ActivityFilter {
+        direction,
+        asset,
+        from_block: params.from_block.unwrap_or(0),
+        to_block: params.to_block.unwrap_or(u64::MAX),
+        category,
+        status,
+        cursor,
+    };
+
+    // one extra row tells us whether another page exists
+    let mut rows = get_address_activity(&client, &address, &filter, limit + 1).await?;
+
+    let next_cursor = if rows.len() as u64 > limit {
+        rows.truncate(limit as usize);
+        rows.last().map(encode_cursor)
+    } else {
+        None
+    };
+
+    let labels = match (rows.last(), rows.first()) {
+        (Some(oldest), Some(newest)) if chain_config.is_evm() => {
+            let hashes: Vec<&str> = rows.iter().map(|row| row.tx_hash.as_str()).collect();
+            get_tx_labels(&client, &hashes, oldest.block_number, newest.block_number).await?
+        }
+        _ => Default::default(),
+    };
+
+    let items = rows
+        .into_iter()
+        .map(|row| {
+            let (category, status) = labels.get(&row.tx_hash).copied().unwrap_or_default();
+            AddressActivity {
+                direction: if row.from_addr == address { "out" } else { "in" },
+                log_index: row.seq.checked_sub(1),
+                asset: if row.kind == "native" {
+                    chain_config.native_symbol().to_string()
+                } else {
+                    row.asset
+                },
+                kind: row.kind,
+                tx_hash: row.tx_hash,
+                block_number: row.block_number,
+                from_addr: row.from_addr,
+                to_addr: row.to_addr,
+                amount: row.amount,
+                category: category_name(category),
+                status: status_name(status),
+            }
+        })
+        .collect();
+
+    Ok(Json(AddressHistoryPage {
+        chain,
+        address,
+        items,
+        next_cursor,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct LiquidityParams {
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LiquidityPosition {
+    pub pool: String,
+    pub token0: String,
+    pub token1: String,
+    // LP tokens still held from this address's own adds: adds minus removes
+    pub lp_amount: String,
+    pub amount0: String,
+    pub amount1: String,
+    pub open: bool,
+    pub adds: u64,
+    pub removes: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+}
+
+#[derive(Serialize)]
+pub struct AddressLiquidity {
+    pub chain: String,
+    pub address: String,
+    pub positions: Vec<LiquidityPosition>,
+    // latest events, newest first
+    pub events: Vec<LiquidityEventRow>,
+}
+
+// GET /v1/{chain}/address/{addr}/liquidity
+pub async fn get_address_liquidity(
+    State(state): State<AppState>,
+    Path((chain, address)): Path<(String, String)>,
+    Query(params): Query<LiquidityParams>,
+) -> Result<Json<AddressLiquidity>, ApiError> {
+    let client = state
+        .client(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+    let chain_config = state
+        .chain(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+
+    if !chain_config.is_evm() {
+        return Err(ApiError::BadRequest(
+            "liquidity events are only decoded on EVM chains".into(),
+        ));
+    }
+
+    let address = normalize_address(chain_config, &address);
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let (positions, events) = tokio::try_join!(
+        get_liquidity_positions(&client, &address),
+        get_liquidity_events(&client, &address, limit),
+    )?;
+
+    let positions = positions
+        .into_iter()
+        .map(|row| LiquidityPosition {
+            // removes of LP tokens received from elsewhere can push the sum below zero
+            open: !row.lp_amount.starts_with('-') && row.lp_amount != "0",
+            pool: row.pool,
+            token0: row.token0,
+            token1: row.token1,
+            lp_amount: row.lp_amount,
+            amount0: row.amount0,
+            amount1: row.amount1,
+            adds: row.adds,
+            removes: row.removes,
+            first_block: row.first_block,
+            last_block: row.last_block,
+        })
+        .collect();
+
+    Ok(Json(AddressLiquidity {
+        chain,
+        address,
+        positions,
+        events,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(block_number: u64, seq: u32, tx_hash: &str) -> AddressActivityRow {
+        AddressActivityRow {
+            kind: "transfer".into(),
+            tx_hash: tx_hash.into(),
+            block_number,
+            seq,
+            from_addr: String::new(),
+            to_addr: String::new(),
+            asset: String::new(),
+            amount: "0".into(),
+        }
+    }
+
+    fn rejected(cursor: &str) -> bool {
+        matches!(parse_cursor(cursor), Err(ApiError::BadRequest(_)))
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = encode_cursor(&row(19_000_000, 7, "0xabc"));
+        let decoded = parse_cursor(&cursor).unwrap();
+        assert_eq!(decoded.block_number, 19_000_000);
+        assert_eq!(decoded.seq, 7);
+        assert_eq!(decoded.tx_hash, "0xabc");
+    }
+
+    #[test]
+    fn cursor_keeps_colons_in_the_hash() {
+        let cursor = encode_cursor(&row(1, 0, "a:b"));
+        assert_eq!(parse_cursor(&cursor).unwrap().tx_hash, "a:b");
+    }
+
+    #[test]
+    fn non_hex_cursors_are_bad_requests() {
+        assert!(rejected("not-a-cursor"));
+        assert!(rejected("zz"));
+        assert!(rejected(""));
+        // odd length
+        assert!(rejected(&encode_cursor(&row(1, 0, "0xabc"))[1..]));
+        // not UTF-8
+        assert!(rejected("ff00"));
+    }
+
+    #[test]
+    fn truncated_cursors_are_bad_requests() {
+        let full = encode_cursor(&row(123, 4, "0xabc"));
+        // every whole-byte prefix up to "123:4:", which has an empty hash
+        for len in (0..=hex::encode("123:4:").len()).step_by(2) {
+            assert!(rejected(&full[..len]), "prefix {:?}", &full[..len]);
+        }
+    }
+
+    #[test]
+    fn malformed_cursors_are_bad_requests() {
+        for raw in ["x:4:0xabc", "123:x:0xabc", "-1:4:0xabc", "123:99999999999:0xabc", "123"] {
+            assert!(rejected(&hex::encode(raw)), "{}", raw);
+        }
+    }
+}
@@ -1,3 +1,58 @@
-pub fn get_block_info(block_number: u64) -> String {
-    format!("Block info for block #{}", block_number)
+use axum::extract::{Path, State};
+use axum::response::Json;
+use serde::Serialize;
+
+use crate::db::blocks::{count_block_token_transfers, get_block_transactions};
+use crate::db::sync_state::get_last_synced_block;
+use crate::handlers::error::ApiError;
+use crate::helper::units::sum_amounts;
+use crate::models::TransactionRow;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct BlockDetails {
+    pub chain: String,
+    pub block_number: u64,
+    pub synced: bool,
+    pub last_synced_block: Option<u64>,
+    pub tx_count: usize,
+    pub token_transfer_count: u64,
+    pub total_value: String,
+    pub transactions: Vec<TransactionRow>,
+}
+
+// GET /v1/{chain}/block/{number}
+pub async fn get_block(
+    State(state): State<AppState>,
+    Path((chain, block_number)): Path<(String, u64)>,
+) -> Result<Json<BlockDetails>, ApiError> {
+    let client = state
+        .client(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+
+    let (transactions, token_transfer_count, last_synced_block) = tokio::try_join!(
+        get_block_transactions(&client, block_number),
+        count_block_token_transfers(&client, block_number),
+        get_last_synced_block(&client, &chain),
+    )?;
+
+    // sync_state is a high-water mark: everything at or below it was fully processed
+    let synced = last_synced_block.is_some_and(|last| block_number <= last);
+
+    if transactions.is_empty() && !synced {
+        return Err(ApiError::NotFound(format!("block {}", block_number)));
+    }
+
+    let total_value = sum_amounts(transactions.iter().map(|tx| tx.value.as_str()));
+
+    Ok(Json(BlockDetails {
+        chain,
+        block_number,
+        synced,
+        last_synced_block,
+        tx_count: transactions.len(),
+        token_transfer_count,
+        total_value,
+        transactions,
+    }))
 }
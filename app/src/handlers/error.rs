@@ -0,0 +1,38 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::json;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("unknown chain: {0}")]
+    UnknownChain(String),
+
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("bad request: {0}")]
+    BadRequest(String),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::UnknownChain(_) | ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Internal(err) => {
+                eprintln!("[API] internal error: {:?}", err);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        let message = match &self {
+            ApiError::Internal(_) => "internal error".to_string(),
+            other => other.to_string(),
+        };
+
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
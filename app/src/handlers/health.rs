@@ -1,6 +1,94 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
 use axum::response::Json;
-use serde_json::json;
+use serde_json::{Value, json};
+
+use crate::config::SyncMode;
+use crate::state::AppState;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 200 when ClickHouse and every active chain's RPC answer and live chains are within
+// `health_max_lag_blocks` of the head; 503 otherwise
+pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let mut healthy = true;
+
+    // all chain databases live on the same ClickHouse server
+    let clickhouse = match state.clickhouse.values().next() {
+        Some(client) => match tokio::time::timeout(
+            PROBE_TIMEOUT,
+            client.query("SELECT 1").fetch_one::<u8>(),
+        )
+        .await
+        {
+            Ok(Ok(_)) => "ok".to_string(),
+            Ok(Err(err)) => format!("error: {}", err),
+            Err(_) => "error: timeout".to_string(),
+        },
+        None => "not configured".to_string(),
+    };
+    if clickhouse != "ok" {
+        healthy = false;
+    }
+
+    let mut chains = BTreeMap::new();
+
+    for chain in state.sync_status.chains() {
+        let mut problems: Vec<String> = vec![];
+
+        if let Some(probe) = state.sync_status.probe(&chain) {
+            match tokio::time::timeout(PROBE_TIMEOUT, probe()).await {
+                Ok(Ok(head)) => state.sync_status.set_head(&chain, head),
+                Ok(Err(err)) => problems.push(format!("rpc error: {:#}", err)),
+                Err(_) => problems.push("rpc timeout".to_string()),
+            }
+        }
+
+        let snapshot = state.sync_status.snapshot(&chain, None);
+
+        if snapshot.stopped {
+            problems.push(format!("fetch loop stopped after {} restart(s)", snapshot.restarts));
+        }
+
+        // backfill and repair are behind the head by design, so lag only counts for live / auto
+        let checks_lag = !matches!(state.sync_status.sync_mode(&chain), Some(SyncMode::Backfill | SyncMode::Repair));
+        if let Some(lag) = snapshot.lag_blocks
+            && checks_lag
+            && lag > state.health_max_lag_blocks
+        {
+            problems.push(format!(
+                "lag {} blocks exceeds {}",
+                lag, state.health_max_lag_blocks
+            ));
+        }
+
+        if !problems.is_empty() {
+            healthy = false;
+        }
+
+        chains.insert(
+            chain,
+            json!({
+                "status": if problems.is_empty() { "ok" } else { "unhealthy" },
+                "head_block": snapshot.head_block,
+                "confirmed_head": snapshot.confirmed_head,
+                "lag_blocks": snapshot.lag_blocks,
+                "problems": problems,
+            }),
+        );
+    }
+
+    let code = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
 
-pub async fn health_check() -> Json<serde_json::Value> {
-    Json(json!({"status":"ok"}))
+    (
+        code,
+        Json(json!({
+            "status": if healthy { "ok" } else { "unhealthy" },
+            "clickhouse": clickhouse,
+            "chains": chains,
+        })),
+    )
 }
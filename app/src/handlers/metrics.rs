@@ -0,0 +1,12 @@
+use axum::http::header;
+use axum::response::IntoResponse;
+
+use crate::services::metrics;
+
+// Prometheus scrape endpoint
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+}
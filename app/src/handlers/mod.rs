@@ -1,4 +1,9 @@
+pub mod addresses;
 pub mod blocks;
+pub mod error;
 pub mod health;
+pub mod metrics;
 pub mod status;
+pub mod stream;
+pub mod transactions;
 
@@ -1,6 +1,51 @@
+use std::collections::BTreeMap;
+
+use axum::extract::State;
 use axum::response::Json;
-use serde_json::json;
+use serde::Serialize;
+
+use crate::db::sync_state::get_last_synced_block;
+use crate::services::sync_status::ChainSnapshot;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct ChainStatus {
+    // true when this process runs a fetch loop for the chain
+    pub active: bool,
+    pub last_synced_block: Option<u64>,
+    #[serde(flatten)]
+    pub progress: ChainSnapshot,
+}
+
+#[derive(Serialize)]
+pub struct StatusReport {
+    pub service: &'static str,
+    pub chains: BTreeMap<String, ChainStatus>,
+}
+
+pub async fn status(State(state): State<AppState>) -> Json<StatusReport> {
+    let active = state.sync_status.chains();
+    let mut chains = BTreeMap::new();
+
+    for (chain, client) in state.clickhouse.iter() {
+        // databases of chains that never ran may not exist yet
+        let last_synced_block = get_last_synced_block(client, chain)
+            .await
+            .ok()
+            .flatten();
+
+        chains.insert(
+            chain.clone(),
+            ChainStatus {
+                active: active.contains(chain),
+                last_synced_block,
+                progress: state.sync_status.snapshot(chain, last_synced_block),
+            },
+        );
+    }
 
-pub async fn status() -> Json<serde_json::Value> {
-    Json(json!({"service":"btc-eth-fetcher"}))
+    Json(StatusReport {
+        service: "btc-eth-fetcher",
+        chains,
+    })
 }
@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use ethers::types::U256;
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::config::{ChainConfig, ChainKind};
+use crate::handlers::addresses::normalize_address;
+use crate::handlers::error::ApiError;
+use crate::models::transaction::Sensivity;
+use crate::services::live_feed::{self, FeedEvent};
+use crate::services::shutdown;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct StreamParams {
+    // comma separated, e.g. "eth,bsc"
+    pub chain: Option<String>,
+    // comma separated; matches either side of the transfer
+    pub addresses: Option<String>,
+    pub token: Option<String>,
+    // in the stored units of the row (wei / sun / BTC / raw token amount)
+    pub min_value: Option<f64>,
+    // red | yellow | green: only transactions at this level or more sensitive
+    pub sensivity: Option<String>,
+}
+
+struct StreamFilter {
+    // every configured chain, for address normalization
+    known: Arc<HashMap<String, ChainConfig>>,
+    chains: Option<HashSet<String>>,
+    addresses: Vec<String>,
+    token: Option<String>,
+    min_value: Option<f64>,
+    max_sensivity: Option<u8>,
+}
+
+impl StreamFilter {
+    fn from_params(
+        params: StreamParams,
+        known: Arc<HashMap<String, ChainConfig>>,
+    ) -> Result<Self, ApiError> {
+        let chains = match params.chain {
+            Some(list) => {
+                let chains: HashSet<String> = split_list(&list).collect();
+                if let Some(unknown) = chains.iter().find(|c| !known.contains_key(c.as_str())) {
+                    return Err(ApiError::UnknownChain(unknown.clone()));
+                }
+                Some(chains)
+            }
+            None => None,
+        };
+
+        let max_sensivity = match params.sensivity.as_deref().map(str::to_lowercase).as_deref() {
+            None => None,
+            Some("red") => Some(Sensivity::Red as u8),
+            Some("yellow") => Some(Sensivity::Yellow as u8),
+            Some("green") => Some(Sensivity::Green as u8),
+            Some(other) => {
+                return Err(ApiError::BadRequest(format!(
+                    "sensivity must be red, yellow or green, got {}",
+                    other
+                )));
+            }
+        };
+
+        Ok(Self {
+            known,
+            chains,
+            addresses: params
+                .addresses
+                .as_deref()
+                .map(|list| split_list(list).collect())
+                .unwrap_or_default(),
+            token: params.token.map(|t| t.trim().to_string()),
+            min_value: params.min_value,
+            max_sensivity,
+        })
+    }
+
+    fn matches(&self, event: &FeedEvent) -> bool {
+        let chain = event.chain();
+
+        if let Some(chains) = &self.chains
+            && !chains.contains(chain)
+        {
+            return false;
+        }
+
+        // every subscriber of the chain needs to know its earlier events were orphaned
+        if let FeedEvent::Reorg { .. } = event {
+            return true;
+        }
+
+        let Some(chain_config) = self.known.get(chain) else {
+            return false;
+        };
+
+        let (from, to, value) = match event {
+            FeedEvent::Transaction { row, .. } => {
+                // token filter asks for token transfers only
+                if self.token.is_some() {
+                    return false;
+                }
+                // sensivity 0 means the chain doesn't rate its transactions
+                if let Some(max) = self.max_sensivity
+                    && (row.sensivity == 0 || row.sensivity > max)
+                {
+                    return false;
+                }
+                (&row.from_addr, &row.to_addr, parse_value(&row.value, false))
+            }
+            FeedEvent::TokenTransfer { row, .. } => {
+                // sensivity is only computed for native transactions
+                if self.max_sensivity.is_some() {
+                    return false;
+                }
+                if let Some(token) = &self.token
+                    && normalize_address(chain_config, token) != row.token_address
+                {
+                    return false;
+                }
+                // Tron stores TRC20 amounts as the raw hex log data
+                let hex = chain_config.kind == ChainKind::Tron;
+                (&row.from_addr, &row.to_addr, parse_value(&row.amount, hex))
+            }
+            FeedEvent::Reorg { .. } => return true,
+        };
+
+        if !self.addresses.is_empty()
+            && !self.addresses.iter().any(|a| {
+                let a = normalize_address(chain_config, a);
+                &a == from || &a == to
+            })
+        {
+            return false;
+        }
+
+        if let Some(min) = self.min_value
+            && value.is_none_or(|v| v < min)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_value(raw: &str, hex: bool) -> Option<f64> {
+    if hex {
+        let digits = raw.trim_start_matches("0x");
+        return U256::from_str_radix(digits, 16).ok()?.to_string().parse().ok();
+    }
+    raw.parse().ok()
+}
+
+// GET /v1/stream?chain=&addresses=&token=&min_value=&sensivity=
+// Server-sent events, one `transaction` / `token_transfer` event per persisted row,
+// plus `reorg` events when rows of the subscribed chains are rolled back.
+pub async fn stream(
+    State(state): State<AppState>,
+    Query(params): Query<StreamParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let filter = StreamFilter::from_params(params, state.chains.clone())?;
+    // keep-alives would hold the connection, and so the graceful shutdown, open forever
+    let events = feed_events(live_feed::subscribe(), filter, shutdown::requested());
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(Duration::from_secs(15))))
+}
+
+// SSE events for the subscriber's feed until the feed closes or `stop` resolves
+fn feed_events(
+    receiver: broadcast::Receiver<FeedEvent>,
+    filter: StreamFilter,
+    stop: impl Future<Output = ()>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let events = stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if filter.matches(&event) => {
+                    let name = match &event {
+                        FeedEvent::Transaction { .. } => "transaction",
+                        FeedEvent::TokenTransfer { .. } => "token_transfer",
+                        FeedEvent::Reorg { .. } => "reorg",
+                    };
+                    let sse = Event::default()
+                        .event(name)
+                        .json_data(&event)
+                        .unwrap_or_else(|_| Event::default().comment("unserializable event"));
+                    return Some((Ok(sse), (receiver, filter)));
+                }
+                Ok(_) => continue,
+                // tell the client it missed events instead of silently dropping them
+                Err(RecvError::Lagged(skipped)) => {
+                    let sse = Event::default().event("lagged").data(skipped.to_string());
+                    return Some((Ok(sse), (receiver, filter)));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    events.take_until(stop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::oneshot;
+
+    fn any_event() -> StreamFilter {
+        let params = StreamParams {
+            chain: None,
+            addresses: None,
+            token: None,
+            min_value: None,
+            sensivity: None,
+        };
+        StreamFilter::from_params(params, Arc::new(HashMap::new())).unwrap()
+    }
+
+    fn reorg() -> FeedEvent {
+        FeedEvent::Reorg {
+            chain: "eth".into(),
+            from_block: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_ends_when_shutdown_is_requested() {
+        let (sender, receiver) = broadcast::channel(16);
+        let (stop, stopped) = oneshot::channel::<()>();
+        let events = feed_events(receiver, any_event(), async {
+            let _ = stopped.await;
+        });
+        tokio::pin!(events);
+
+        sender.send(reorg()).unwrap();
+        assert!(events.next().await.is_some());
+
+        stop.send(()).unwrap();
+        // the feed is still open, only the stop request ends the stream
+        let end = tokio::time::timeout(Duration::from_secs(1), events.next()).await;
+        assert!(matches!(end, Ok(None)));
+        drop(sender);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_when_the_feed_closes() {
+        let (sender, receiver) = broadcast::channel(16);
+        let events = feed_events(receiver, any_event(), std::future::pending());
+        tokio::pin!(events);
+
+        drop(sender);
+        assert!(events.next().await.is_none());
+    }
+}
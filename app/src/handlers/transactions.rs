@@ -0,0 +1,59 @@
+use axum::extract::{Path, State};
+use axum::response::Json;
+use serde::Serialize;
+
+use crate::db::transactions::{
+    get_contract_calls_by_tx,
+    get_money_flows_by_tx,
+    get_token_transfers_by_tx,
+    get_transaction,
+};
+use crate::handlers::addresses::{category_name, status_name};
+use crate::handlers::error::ApiError;
+use crate::models::{ContractCallRow, MoneyFlowRow, TokenTransferRow, TransactionRow};
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct TransactionDetails {
+    pub chain: String,
+    // names of `transaction.category` / `transaction.status`, EVM only
+    pub category: Option<&'static str>,
+    pub status: Option<&'static str>,
+    pub transaction: TransactionRow,
+    pub token_transfers: Vec<TokenTransferRow>,
+    pub money_flows: Vec<MoneyFlowRow>,
+    pub contract_calls: Vec<ContractCallRow>,
+}
+
+// GET /v1/{chain}/tx/{hash}
+pub async fn get_tx(
+    State(state): State<AppState>,
+    Path((chain, hash)): Path<(String, String)>,
+) -> Result<Json<TransactionDetails>, ApiError> {
+    let client = state
+        .client(&chain)
+        .ok_or_else(|| ApiError::UnknownChain(chain.clone()))?;
+
+    // hashes are stored lowercase on every chain
+    let hash = hash.trim().to_lowercase();
+
+    let transaction = get_transaction(&client, &hash)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("transaction {}", hash)))?;
+
+    let (token_transfers, money_flows, contract_calls) = tokio::try_join!(
+        get_token_transfers_by_tx(&client, &hash),
+        get_money_flows_by_tx(&client, &hash),
+        get_contract_calls_by_tx(&client, &hash),
+    )?;
+
+    Ok(Json(TransactionDetails {
+        chain,
+        category: category_name(transaction.category),
+        status: status_name(transaction.status),
+        transaction,
+        token_transfers,
+        money_flows,
+        contract_calls,
+    }))
+}
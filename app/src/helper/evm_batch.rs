@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use ethers::types::{Address, Bytes, H256, TransactionReceipt, U256};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::helper::evm_rpc::json_rpc_fault;
+use crate::helper::rpc_pool::RpcPool;
+use crate::services::metrics;
+
+/// JSON-RPC batch requests against an EVM node; the ethers provider sends one call per request.
+pub struct EvmBatchClient {
+    chain: String,
+    pool: Arc<RpcPool>,
+    batch_size: usize,
+}
+
+/// State of an address at the latest block.
+pub struct AccountState {
+    pub balance: U256,
+    pub nonce: U256,
+    pub is_contract: bool,
+}
+
+fn decode<T: DeserializeOwned>(value: Option<Value>) -> Result<T> {
+    Ok(serde_json::from_value(value.context("batch response too short")?)?)
+}
+
+impl EvmBatchClient {
+    pub fn new(chain: &str, pool: Arc<RpcPool>, batch_size: usize) -> Self {
+        Self {
+            chain: chain.to_string(),
+            pool,
+            batch_size,
+        }
+    }
+
+    /// Sends `calls` in batches of at most `batch_size`; results come back in call order.
+    async fn batch(&self, label: &str, calls: Vec<(&str, Value)>) -> Result<Vec<Value>> {
+        let tag = self.chain.to_uppercase();
+        let mut results = Vec::with_capacity(calls.len());
+
+        for chunk in calls.chunks(self.batch_size) {
+            let body: Vec<Value> = chunk
+                .iter()
+                .enumerate()
+                .map(|(id, (method, params))| {
+                    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+                })
+                .collect();
+
+            let text = metrics::rpc(
+                &self.chain,
+                label,
+                self.pool
+                    .send_checked(label, |http, url| http.post(url).json(&body), json_rpc_fault),
+            )
+            .await
+            .with_context(|| format!("[{}] {} batch request failed", tag, label))?;
+            let response: Value = serde_json::from_str(&text)
+                .with_context(|| format!("[{}] {} batch response is not JSON", tag, label))?;
+
+            // nodes without batch support (or over their batch limit) answer with a single error
+            let Value::Array(items) = response else {
+                bail!("[{}] {} batch rejected: {}", tag, label, response);
+            };
+
+            let mut by_id: HashMap<u64, Value> = HashMap::with_capacity(items.len());
+            for mut item in items {
+                let id = item["id"].as_u64().context("batch response without id")?;
+                let Some((method, _)) = chunk.get(id as usize) else {
+                    bail!("[{}] {} batch response with unknown id {}", tag, label, id);
+                };
+                if let Some(error) = item.get("error").filter(|error| !error.is_null()) {
+                    bail!("[{}] {} failed in batch: {}", tag, method, error);
+                }
+                by_id.insert(id, item["result"].take());
+            }
+
+            for (id, (method, _)) in chunk.iter().enumerate() {
+                let result = by_id
+                    .remove(&(id as u64))
+                    .with_context(|| format!("[{}] no batch response for {}", tag, method))?;
+                results.push(result);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Receipts of `hashes` in order; None for a transaction the node doesn't know.
+    pub async fn transaction_receipts(
+        &self,
+        hashes: &[H256],
+    ) -> Result<Vec<Option<TransactionReceipt>>> {
+        let calls = hashes
+            .iter()
+            .map(|hash| ("eth_getTransactionReceipt", json!([hash])))
+            .collect();
+
+        self.batch("batch_get_transaction_receipt", calls)
+            .await?
+            .into_iter()
+            .map(|result| decode(Some(result)))
+            .collect()
+    }
+
+    /// Balance, nonce and code of every address in one round trip per batch.
+    pub async fn account_states(&self, addrs: &[Address]) -> Result<Vec<AccountState>> {
+        let mut calls = Vec::with_capacity(addrs.len() * 3);
+        for addr in addrs {
+            calls.push(("eth_getBalance", json!([addr, "latest"])));
+            calls.push(("eth_getTransactionCount", json!([addr, "latest"])));
+            calls.push(("eth_getCode", json!([addr, "latest"])));
+        }
+
+        let mut results = self.batch("batch_account_state", calls).await?.into_iter();
+
+        let mut states = Vec::with_capacity(addrs.len());
+        for _ in addrs {
+            let balance = decode(results.next())?;
+            let nonce = decode(results.next())?;
+            let code: Bytes = decode(results.next())?;
+
+            states.push(AccountState {
+                balance,
+                nonce,
+                is_contract: !code.0.is_empty(),
+            });
+        }
+
+        Ok(states)
+    }
+}
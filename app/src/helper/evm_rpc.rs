@@ -0,0 +1,178 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use ethers::providers::{JsonRpcClient, JsonRpcError, Provider, ProviderError, RpcError};
+use serde::Deserialize;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::helper::rpc_pool::{BodyFault, PoolError, RpcPool};
+
+// JSON-RPC error codes providers answer rate limited calls with (Infura, Alchemy, ...)
+const RATE_LIMIT_CODES: [i64; 3] = [-32005, -32029, 429];
+const RATE_LIMIT_MESSAGES: [&str; 3] = ["rate limit", "too many requests", "limit exceeded"];
+// a node behind the others doesn't have the block yet; another endpoint may
+const LAGGING_MESSAGES: [&str; 3] = ["header not found", "unknown block", "missing trie node"];
+
+fn error_fault(error: &Value) -> Option<BodyFault> {
+    let code = error["code"].as_i64();
+    let message = error["message"].as_str().unwrap_or_default().to_lowercase();
+
+    if code.is_some_and(|code| RATE_LIMIT_CODES.contains(&code))
+        || RATE_LIMIT_MESSAGES.iter().any(|m| message.contains(m))
+    {
+        return Some(BodyFault::RateLimited);
+    }
+    if LAGGING_MESSAGES.iter().any(|m| message.contains(m)) {
+        return Some(BodyFault::Unavailable(message));
+    }
+    None
+}
+
+/// Errors in a JSON-RPC response (single or batch) that another endpoint may not give:
+/// rate limits and lagging nodes. Any other error is the call's own and goes to the caller.
+pub fn json_rpc_fault(body: &str) -> Option<BodyFault> {
+    // receipts of a full block are megabytes; most bodies carry no error at all
+    if !body.contains("\"error\"") {
+        return None;
+    }
+
+    match serde_json::from_str::<Value>(body).ok()? {
+        Value::Array(items) => items.iter().find_map(|item| error_fault(&item["error"])),
+        response => error_fault(&response["error"]),
+    }
+}
+
+/// ethers provider whose calls go through the chain's `RpcPool`.
+pub type EvmProvider = Provider<PooledHttp>;
+
+/// JSON-RPC over HTTP like ethers' `Http`, but sent through an `RpcPool`.
+#[derive(Debug)]
+pub struct PooledHttp {
+    pool: Arc<RpcPool>,
+    next_id: AtomicU64,
+}
+
+impl PooledHttp {
+    pub fn new(pool: Arc<RpcPool>) -> Self {
+        Self {
+            pool,
+            next_id: AtomicU64::new(1),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PooledHttpError {
+    #[error(transparent)]
+    Pool(#[from] PoolError),
+
+    #[error(transparent)]
+    Rpc(#[from] JsonRpcError),
+
+    #[error("invalid JSON-RPC response: {err}. Response: {text}")]
+    Serde { err: serde_json::Error, text: String },
+}
+
+impl RpcError for PooledHttpError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            PooledHttpError::Rpc(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            PooledHttpError::Serde { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PooledHttpError> for ProviderError {
+    fn from(err: PooledHttpError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(err))
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    #[serde(default)]
+    result: Value,
+    error: Option<JsonRpcError>,
+}
+
+#[async_trait]
+impl JsonRpcClient for PooledHttp {
+    type Error = PooledHttpError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, PooledHttpError>
+    where
+        T: std::fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+
+        let text = self
+            .pool
+            .send_checked(method, |http, url| http.post(url).json(&body), json_rpc_fault)
+            .await?;
+
+        let response: RpcResponse = serde_json::from_str(&text)
+            .map_err(|err| PooledHttpError::Serde { err, text: text.clone() })?;
+        if let Some(error) = response.error {
+            return Err(error.into());
+        }
+
+        serde_json::from_value(response.result).map_err(|err| PooledHttpError::Serde { err, text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_carry_no_fault() {
+        assert_eq!(json_rpc_fault(r#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#), None);
+        assert_eq!(json_rpc_fault("not json, \"error\""), None);
+    }
+
+    #[test]
+    fn rate_limits_by_code_or_message() {
+        let by_code = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"slow down"}}"#;
+        assert_eq!(json_rpc_fault(by_code), Some(BodyFault::RateLimited));
+
+        let by_message = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"Too Many Requests"}}"#;
+        assert_eq!(json_rpc_fault(by_message), Some(BodyFault::RateLimited));
+    }
+
+    #[test]
+    fn lagging_nodes_are_unavailable() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"header not found"}}"#;
+        assert_eq!(
+            json_rpc_fault(body),
+            Some(BodyFault::Unavailable("header not found".into()))
+        );
+    }
+
+    #[test]
+    fn faults_inside_a_batch() {
+        let body = r#"[{"jsonrpc":"2.0","id":0,"result":null},
+                       {"jsonrpc":"2.0","id":1,"error":{"code":429,"message":"x"}}]"#;
+        assert_eq!(json_rpc_fault(body), Some(BodyFault::RateLimited));
+    }
+
+    #[test]
+    fn call_errors_go_to_the_caller() {
+        let reverted = r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted"}}"#;
+        assert_eq!(json_rpc_fault(reverted), None);
+
+        let not_found = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"the method eth_getBlockReceipts does not exist"}}"#;
+        assert_eq!(json_rpc_fault(not_found), None);
+    }
+}
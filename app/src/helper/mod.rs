@@ -1 +1,5 @@
-pub mod tron;
\ No newline at end of file
+pub mod evm_batch;
+pub mod evm_rpc;
+pub mod rpc_pool;
+pub mod tron;
+pub mod units;
\ No newline at end of file
@@ -0,0 +1,504 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, StatusCode, header::RETRY_AFTER};
+use tokio::time::sleep;
+
+use crate::config::{RpcEndpoint, redact_url};
+use crate::services::metrics;
+
+// weight of the latest outcome in an endpoint's health score
+const HEALTH_ALPHA: f64 = 0.2;
+// an endpoint that keeps failing still gets the odd request, so it can recover
+const MIN_HEALTH: f64 = 0.05;
+// error bodies are cut to this many characters
+const MAX_ERROR_BODY: usize = 200;
+
+/// How failed calls are retried across the pool.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    // used when a 429 carries no Retry-After
+    pub rate_limit_cooldown: Duration,
+}
+
+impl RetryPolicy {
+    /// Full jitter: a random delay up to `backoff * 2^(retry - 1)`, capped at `max_backoff`.
+    fn delay(&self, retry: u32) -> Duration {
+        let cap = self
+            .backoff
+            .saturating_mul(1 << (retry.saturating_sub(1)).min(16))
+            .min(self.max_backoff);
+        let millis = rand::thread_rng().gen_range(0..=cap.as_millis() as u64);
+        Duration::from_millis(millis)
+    }
+}
+
+/// A failure reported in the body of a successful HTTP response, e.g. a JSON-RPC error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodyFault {
+    // provider rate limit: the endpoint cools down as after an HTTP 429
+    RateLimited,
+    // this endpoint can't serve the call right now (e.g. a lagging node), another one may
+    Unavailable(String),
+}
+
+/// One failed attempt. Messages only name the endpoint by its redacted label.
+#[derive(Debug, thiserror::Error)]
+pub enum PoolError {
+    #[error("{endpoint} rate limited the request (HTTP 429)")]
+    RateLimited { endpoint: String },
+
+    #[error("{endpoint} timed out")]
+    Timeout { endpoint: String },
+
+    #[error("{endpoint} could not serve the call: {message}")]
+    Unavailable { endpoint: String, message: String },
+
+    #[error("{endpoint} answered HTTP {status}: {body}")]
+    Status {
+        endpoint: String,
+        status: StatusCode,
+        body: String,
+    },
+
+    #[error("{endpoint} request failed: {source}")]
+    Transport {
+        endpoint: String,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+impl PoolError {
+    fn outcome(&self) -> &'static str {
+        match self {
+            PoolError::RateLimited { .. } => "rate_limited",
+            PoolError::Timeout { .. } => "timeout",
+            PoolError::Unavailable { .. } => "unavailable",
+            PoolError::Status { .. } => "status",
+            PoolError::Transport { .. } => "transport",
+        }
+    }
+
+    /// A malformed or oversized request fails the same way on every endpoint.
+    fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            PoolError::Status {
+                status: StatusCode::BAD_REQUEST | StatusCode::PAYLOAD_TOO_LARGE,
+                ..
+            }
+        )
+    }
+}
+
+struct Health {
+    // moving average of successes (1.0) and failures (0.0)
+    score: f64,
+    // skipped until then after a 429
+    cooldown_until: Option<Instant>,
+}
+
+struct Endpoint {
+    url: String,
+    // scheme + host, used in logs, errors and metric labels
+    label: String,
+    weight: u32,
+    health: Mutex<Health>,
+}
+
+impl Endpoint {
+    fn cooling_until(&self, now: Instant) -> Option<Instant> {
+        let health = self.health.lock().unwrap();
+        health.cooldown_until.filter(|until| *until > now)
+    }
+
+    fn record(&self, chain: &str, success: bool, cooldown: Option<Duration>) {
+        let mut health = self.health.lock().unwrap();
+        let outcome = if success { 1.0 } else { 0.0 };
+        health.score = health.score * (1.0 - HEALTH_ALPHA) + outcome * HEALTH_ALPHA;
+        if let Some(cooldown) = cooldown {
+            health.cooldown_until = Some(Instant::now() + cooldown);
+        }
+        metrics::set_endpoint_health(chain, &self.label, health.score);
+    }
+}
+
+/// The endpoints of one chain behind a single client: requests go to a weighted pick of
+/// the healthy endpoints and failed ones are retried on the next, with backoff.
+pub struct RpcPool {
+    chain: String,
+    http: Client,
+    endpoints: Vec<Endpoint>,
+    policy: RetryPolicy,
+}
+
+impl fmt::Debug for RpcPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<&str> = self.endpoints.iter().map(|e| e.label.as_str()).collect();
+        f.debug_struct("RpcPool")
+            .field("chain", &self.chain)
+            .field("endpoints", &labels)
+            .finish()
+    }
+}
+
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let seconds = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+impl RpcPool {
+    pub fn new(
+        chain: &str,
+        endpoints: &[RpcEndpoint],
+        timeout: Duration,
+        policy: RetryPolicy,
+    ) -> anyhow::Result<Self> {
+        if endpoints.is_empty() {
+            anyhow::bail!("[{}] no RPC endpoint configured", chain.to_uppercase());
+        }
+
+        let http = Client::builder().timeout(timeout).build()?;
+
+        let mut pooled: Vec<Endpoint> = Vec::with_capacity(endpoints.len());
+        for (index, endpoint) in endpoints.iter().enumerate() {
+            let mut label = redact_url(&endpoint.url);
+            // two keys on the same provider would otherwise share their metrics
+            if pooled.iter().any(|other| other.label == label) {
+                label = format!("{}#{}", label, index);
+            }
+            metrics::set_endpoint_health(chain, &label, 1.0);
+
+            pooled.push(Endpoint {
+                url: endpoint.url.clone(),
+                label,
+                weight: endpoint.weight,
+                health: Mutex::new(Health {
+                    score: 1.0,
+                    cooldown_until: None,
+                }),
+            });
+        }
+
+        Ok(Self {
+            chain: chain.to_string(),
+            http,
+            endpoints: pooled,
+            policy,
+        })
+    }
+
+    /// Weighted random endpoint, preferring ones not `tried` yet; endpoints cooling down
+    /// after a 429 are skipped. Err holds the end of the shortest cooldown when all are.
+    fn pick(&self, tried: &[usize]) -> Result<usize, Instant> {
+        let now = Instant::now();
+        let mut available = vec![];
+        let mut earliest: Option<Instant> = None;
+
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            match endpoint.cooling_until(now) {
+                Some(until) => earliest = Some(earliest.map_or(until, |e| e.min(until))),
+                None => available.push(index),
+            }
+        }
+
+        let fresh: Vec<usize> = available
+            .iter()
+            .copied()
+            .filter(|index| !tried.contains(index))
+            .collect();
+        let candidates = if fresh.is_empty() { available } else { fresh };
+
+        let share = |index: usize| {
+            let endpoint = &self.endpoints[index];
+            let score = endpoint.health.lock().unwrap().score;
+            endpoint.weight as f64 * score.max(MIN_HEALTH)
+        };
+
+        let total: f64 = candidates.iter().map(|&index| share(index)).sum();
+        if candidates.is_empty() || total <= 0.0 {
+            return Err(earliest.unwrap_or(now));
+        }
+
+        let mut point = rand::thread_rng().gen_range(0.0..total);
+        for &index in &candidates {
+            point -= share(index);
+            if point < 0.0 {
+                return Ok(index);
+            }
+        }
+        Ok(candidates[candidates.len() - 1])
+    }
+
+    async fn attempt(
+        &self,
+        endpoint: &Endpoint,
+        request: RequestBuilder,
+        inspect: &(dyn Fn(&str) -> Option<BodyFault> + Sync),
+    ) -> Result<String, PoolError> {
+        let label = || endpoint.label.clone();
+        // reqwest errors print the URL, which may carry the provider key
+        let transport = |err: reqwest::Error| {
+            if err.is_timeout() {
+                PoolError::Timeout { endpoint: label() }
+            } else {
+                PoolError::Transport {
+                    endpoint: label(),
+                    source: err.without_url(),
+                }
+            }
+        };
+
+        let response = request.send().await.map_err(transport)?;
+        let status = response.status();
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let cooldown = retry_after(response.headers()).unwrap_or(self.policy.rate_limit_cooldown);
+            endpoint.record(&self.chain, false, Some(cooldown));
+            return Err(PoolError::RateLimited { endpoint: label() });
+        }
+
+        let body = response.text().await.map_err(transport)?;
+        if !status.is_success() {
+            return Err(PoolError::Status {
+                endpoint: label(),
+                status,
+                body: body.chars().take(MAX_ERROR_BODY).collect(),
+            });
+        }
+
+        match inspect(&body) {
+            None => Ok(body),
+            Some(BodyFault::RateLimited) => {
+                endpoint.record(&self.chain, false, Some(self.policy.rate_limit_cooldown));
+                Err(PoolError::RateLimited { endpoint: label() })
+            }
+            Some(BodyFault::Unavailable(message)) => Err(PoolError::Unavailable {
+                endpoint: label(),
+                message: message.chars().take(MAX_ERROR_BODY).collect(),
+            }),
+        }
+    }
+
+    /// Sends the request `build` makes for an endpoint URL and returns the response body.
+    /// Failures move on to another endpoint, up to `max_retries` times.
+    pub async fn send<F>(&self, method: &str, build: F) -> Result<String, PoolError>
+    where
+        F: Fn(&Client, &str) -> RequestBuilder,
+    {
+        self.send_checked(method, build, |_| None).await
+    }
+
+    /// Like `send`, but `inspect` also looks at successful bodies; a fault it finds counts
+    /// against the endpoint and is retried like a failed request.
+    pub async fn send_checked<F, C>(&self, method: &str, build: F, inspect: C) -> Result<String, PoolError>
+    where
+        F: Fn(&Client, &str) -> RequestBuilder,
+        C: Fn(&str) -> Option<BodyFault> + Sync,
+    {
+        let mut tried: Vec<usize> = vec![];
+        let mut retries = 0;
+
+        loop {
+            let index = loop {
+                match self.pick(&tried) {
+                    Ok(index) => break index,
+                    Err(until) => sleep(until.saturating_duration_since(Instant::now())).await,
+                }
+            };
+            let endpoint = &self.endpoints[index];
+
+            let started = Instant::now();
+            let result = self
+                .attempt(endpoint, build(&self.http, &endpoint.url), &inspect)
+                .await;
+            let outcome = result.as_ref().map_or_else(PoolError::outcome, |_| "ok");
+            metrics::record_endpoint_request(&self.chain, &endpoint.label, outcome, started.elapsed());
+
+            let err = match result {
+                Ok(body) => {
+                    endpoint.record(&self.chain, true, None);
+                    return Ok(body);
+                }
+                Err(err) => err,
+            };
+
+            // a 429 already counted against the endpoint when its cooldown was set
+            if !matches!(err, PoolError::RateLimited { .. }) {
+                endpoint.record(&self.chain, false, None);
+            }
+
+            if !err.is_retryable() || retries >= self.policy.max_retries {
+                return Err(err);
+            }
+            retries += 1;
+            metrics::record_rpc_retry(&self.chain, method);
+
+            println!(
+                "[{}] {} failed ({}), retry {} of {}",
+                self.chain.to_uppercase(),
+                method,
+                err,
+                retries,
+                self.policy.max_retries
+            );
+
+            tried.push(index);
+            if tried.len() == self.endpoints.len() {
+                tried.clear();
+            }
+
+            sleep(self.policy.delay(retries)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1_000),
+            rate_limit_cooldown: Duration::from_secs(30),
+        }
+    }
+
+    fn pool(weights: &[u32]) -> RpcPool {
+        let endpoints: Vec<RpcEndpoint> = weights
+            .iter()
+            .enumerate()
+            .map(|(i, &weight)| RpcEndpoint {
+                url: format!("http://node{}.test", i),
+                weight,
+            })
+            .collect();
+        RpcPool::new("test", &endpoints, Duration::from_secs(1), policy()).unwrap()
+    }
+
+    // how often each endpoint is picked out of `rounds`
+    fn pick_counts(pool: &RpcPool, tried: &[usize], rounds: usize) -> Vec<usize> {
+        let mut counts = vec![0; pool.endpoints.len()];
+        for _ in 0..rounds {
+            counts[pool.pick(tried).unwrap()] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn delay_stays_within_the_exponential_cap() {
+        let policy = policy();
+        for retry in 0u32..40 {
+            let cap = Duration::from_millis(100 << retry.saturating_sub(1).min(16))
+                .min(policy.max_backoff);
+            for _ in 0..200 {
+                assert!(policy.delay(retry) <= cap, "retry {} exceeded {:?}", retry, cap);
+            }
+        }
+    }
+
+    #[test]
+    fn delay_is_jittered() {
+        let policy = policy();
+        let delays: HashSet<Duration> = (0..200).map(|_| policy.delay(5)).collect();
+        assert!(delays.len() > 1);
+    }
+
+    #[test]
+    fn pick_follows_the_weights() {
+        let counts = pick_counts(&pool(&[1, 3]), &[], 8_000);
+        // expected 2000 / 6000
+        assert!((1_500..2_500).contains(&counts[0]), "{:?}", counts);
+    }
+
+    #[test]
+    fn pick_prefers_endpoints_not_tried_yet() {
+        let pool = pool(&[1, 1, 1]);
+        let counts = pick_counts(&pool, &[0, 2], 500);
+        assert_eq!(counts, vec![0, 500, 0]);
+
+        // once all were tried, any of them may be picked again
+        let counts = pick_counts(&pool, &[0, 1, 2], 3_000);
+        assert!(counts.iter().all(|&count| count > 0), "{:?}", counts);
+    }
+
+    #[test]
+    fn pick_skips_endpoints_cooling_down() {
+        let pool = pool(&[1, 1, 1]);
+        pool.endpoints[1].record("test", false, Some(Duration::from_secs(60)));
+
+        let counts = pick_counts(&pool, &[], 3_000);
+        assert_eq!(counts[1], 0);
+        assert!(counts[0] > 0 && counts[2] > 0);
+    }
+
+    #[test]
+    fn pick_reports_the_earliest_cooldown_when_all_cool_down() {
+        let pool = pool(&[1, 1]);
+        pool.endpoints[0].record("test", false, Some(Duration::from_secs(60)));
+        pool.endpoints[1].record("test", false, Some(Duration::from_secs(5)));
+
+        let until = pool.pick(&[]).unwrap_err();
+        let left = until.saturating_duration_since(Instant::now());
+        assert!(left <= Duration::from_secs(5) && left > Duration::from_secs(4));
+    }
+
+    #[test]
+    fn unhealthy_endpoints_get_a_small_share() {
+        let pool = pool(&[1, 1]);
+        for _ in 0..50 {
+            pool.endpoints[0].record("test", false, None);
+        }
+
+        let counts = pick_counts(&pool, &[], 10_000);
+        // MIN_HEALTH keeps it reachable: about 0.05 / 1.05 of the picks
+        assert!(counts[0] > 0 && counts[0] < 1_500, "{:?}", counts);
+    }
+
+    #[test]
+    fn endpoints_recover_after_cooldown_and_successes() {
+        let pool = pool(&[1, 1]);
+        for _ in 0..50 {
+            pool.endpoints[0].record("test", false, None);
+        }
+        pool.endpoints[0].record("test", false, Some(Duration::from_millis(20)));
+        assert_eq!(pick_counts(&pool, &[], 500)[0], 0);
+
+        std::thread::sleep(Duration::from_millis(40));
+        for _ in 0..50 {
+            pool.endpoints[0].record("test", true, None);
+        }
+
+        let counts = pick_counts(&pool, &[], 8_000);
+        // back to about half the picks
+        assert!((3_000..5_000).contains(&counts[0]), "{:?}", counts);
+    }
+
+    #[test]
+    fn bad_requests_are_not_retried() {
+        let status = |status| PoolError::Status {
+            endpoint: "http://node.test".into(),
+            status,
+            body: String::new(),
+        };
+        assert!(!status(StatusCode::BAD_REQUEST).is_retryable());
+        assert!(!status(StatusCode::PAYLOAD_TOO_LARGE).is_retryable());
+        assert!(status(StatusCode::BAD_GATEWAY).is_retryable());
+        assert!(
+            PoolError::Unavailable {
+                endpoint: "http://node.test".into(),
+                message: "header not found".into(),
+            }
+            .is_retryable()
+        );
+    }
+}
@@ -1,48 +1,59 @@
 use anyhow::{Result, anyhow, Context};
 use serde_json::Value;
-use reqwest::Client;
-// use std::time::Duration; // benazar Tokio behtare
-use tokio::time::{sleep, Duration};
+use std::sync::Arc;
 
-use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::header::HeaderValue;
+
+use crate::helper::rpc_pool::RpcPool;
+use crate::services::metrics;
 
 pub struct TronClient {
-    base_url: String,
-    http: Client,
+    pool: Arc<RpcPool>,
+    api_key: Option<HeaderValue>,
 }
 
 impl TronClient {
-    pub fn new(base_url: String, api_key: Option<String>) -> Self {
-        let mut headers = HeaderMap::new();
+    pub fn new(pool: Arc<RpcPool>, api_key: Option<String>) -> Result<Self> {
+        let api_key = match api_key {
+            Some(key) => {
+                let mut value = HeaderValue::from_str(&key).context("invalid TRON api_key")?;
+                value.set_sensitive(true);
+                Some(value)
+            }
+            None => None,
+        };
 
-        if let Some(key) = api_key {
-            headers.insert(
-                "TRON-PRO-API-KEY",
-                HeaderValue::from_str(&key).unwrap(),
-            );
-        }
+        Ok(Self { pool, api_key })
+    }
 
-        let http = Client::builder()
-            .default_headers(headers)
-            .timeout(Duration::from_secs(20))
-            .build()
-            .unwrap();
+    /// POST `path` on one of the pooled nodes, failing over to another on errors
+    async fn post(&self, method: &str, path: &str, body: Option<Value>) -> Result<Value> {
+        let text = metrics::rpc(
+            "tron",
+            method,
+            self.pool.send(method, |http, base_url| {
+                let mut request = http.post(format!("{}{}", base_url, path));
+                if let Some(key) = &self.api_key {
+                    request = request.header("TRON-PRO-API-KEY", key.clone());
+                }
+                if let Some(body) = &body {
+                    request = request.json(body);
+                }
+                request
+            }),
+        )
+        .await?;
 
-        Self { http, base_url }
+        Ok(serde_json::from_str(&text)?)
     }
 
     /// Latest block number
     pub async fn get_block_number(&self) -> Result<u64> {
-        let url = format!("{}/wallet/getnowblock", self.base_url);
-
-        let resp = self.http
-            .post(&url)
-            .send()
+        let json = self
+            .post("getnowblock", "/wallet/getnowblock", None)
             .await
             .context("getnowblock request failed")?;
 
-        let json: Value = resp.json().await?;
-
         json["block_header"]["raw_data"]["number"]
             .as_u64()
             .ok_or_else(|| anyhow!("Invalid getnowblock response"))
@@ -50,53 +61,23 @@ impl TronClient {
 
     /// Full block by number
     pub async fn get_block_by_number(&self, block: u64) -> Result<Value> {
-        let url = format!("{}/wallet/getblockbynum", self.base_url);
-
-        let mut last_err = None;
-
-        for attempt in 1..=3 {
-            let resp = self.http
-                .post(&url)
-                .json(&serde_json::json!({ "num": block }))
-                .send()
-                .await;
-
-            match resp {
-                Ok(r) => {
-                    let json: Value = r.json().await?;
-                    return Ok(json);
-                }
-                Err(e) => {
-                    last_err = Some(e);
-                    let delay = attempt * 2;
-                    eprintln!(
-                        "[TRON] retry getblock {} (attempt {})",
-                        block, attempt
-                    );
-                    sleep(Duration::from_secs(delay)).await;
-                }
-            }
-        }
-
-        Err(anyhow!(
-            "getblockbynum failed after retries for {}: {:?}",
-            block,
-            last_err
-        ))
+        self.post(
+            "getblockbynum",
+            "/wallet/getblockbynum",
+            Some(serde_json::json!({ "num": block })),
+        )
+        .await
+        .with_context(|| format!("getblockbynum failed for {}", block))
     }
 
     /// Transaction receipt (logs, status, etc.)
     pub async fn get_transaction_info(&self, tx_id: &str) -> Result<Value> {
-        let url = format!("{}/wallet/gettransactioninfobyid", self.base_url);
-
-        let resp = self.http
-            .post(&url)
-            .json(&serde_json::json!({ "value": tx_id }))
-            .send()
-            .await
-            .with_context(|| format!("gettransactioninfobyid failed: {}", tx_id))?;
-
-        let json: Value = resp.json().await?;
-        Ok(json)
+        self.post(
+            "gettransactioninfobyid",
+            "/wallet/gettransactioninfobyid",
+            Some(serde_json::json!({ "value": tx_id })),
+        )
+        .await
+        .with_context(|| format!("gettransactioninfobyid failed: {}", tx_id))
     }
 }
@@ -0,0 +1,129 @@
+use ethers::types::U256;
+
+/// Formats a raw integer amount (as stored in ClickHouse) using the token decimals,
+/// e.g. ("-1500000", 6) -> "-1.5".
+pub fn format_units(raw: &str, decimals: u8) -> String {
+    let (sign, digits) = match raw.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", raw),
+    };
+
+    let decimals = decimals as usize;
+    if decimals == 0 || digits.is_empty() {
+        return raw.to_string();
+    }
+
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (int_part, frac_part) = padded.split_at(padded.len() - decimals);
+    let frac_part = frac_part.trim_end_matches('0');
+
+    if frac_part.is_empty() {
+        format!("{}{}", sign, int_part)
+    } else {
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+/// Sums stored amount strings. EVM / Tron values are integer base units and are
+/// summed exactly; BTC values are stored as decimal coins and fall back to f64,
+/// as does a sum that overflows U256.
+pub fn sum_amounts<'a>(values: impl IntoIterator<Item = &'a str>) -> String {
+    let values: Vec<&str> = values.into_iter().collect();
+
+    let total = values.iter().try_fold(U256::zero(), |acc, v| {
+        acc.checked_add(U256::from_dec_str(v).ok()?)
+    });
+
+    match total {
+        Some(total) => total.to_string(),
+        None => values
+            .iter()
+            .map(|v| v.parse::<f64>().unwrap_or(0.0))
+            .sum::<f64>()
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_units_zero() {
+        assert_eq!(format_units("0", 18), "0");
+        assert_eq!(format_units("0", 6), "0");
+        assert_eq!(format_units("0", 0), "0");
+    }
+
+    #[test]
+    fn format_units_trims_trailing_fraction_zeros() {
+        assert_eq!(format_units("1500000", 6), "1.5");
+        assert_eq!(format_units("1000000", 6), "1");
+        assert_eq!(format_units("-1500000", 6), "-1.5");
+        assert_eq!(format_units("1230000000000000000", 18), "1.23");
+    }
+
+    #[test]
+    fn format_units_pads_small_amounts() {
+        assert_eq!(format_units("1", 18), "0.000000000000000001");
+        assert_eq!(format_units("-25", 4), "-0.0025");
+    }
+
+    #[test]
+    fn format_units_without_decimals() {
+        assert_eq!(format_units("1500000", 0), "1500000");
+        assert_eq!(format_units("-7", 0), "-7");
+    }
+
+    #[test]
+    fn format_units_beyond_18_decimals() {
+        assert_eq!(format_units("1", 24), "0.000000000000000000000001");
+        assert_eq!(format_units("1000000000000000000000000", 24), "1");
+    }
+
+    #[test]
+    fn format_units_above_u128() {
+        // u128::MAX is 340282366920938463463374607431768211455
+        let raw = "1000000000000000000000000000000000000000000000000000000000000";
+        assert!(raw.parse::<u128>().is_err());
+        assert_eq!(format_units(raw, 18), "1000000000000000000000000000000000000000000");
+        assert_eq!(
+            format_units("1000000000000000000000000000000000000000000000000000000000001", 18),
+            "1000000000000000000000000000000000000000000.000000000000000001"
+        );
+    }
+
+    #[test]
+    fn sum_amounts_of_nothing_is_zero() {
+        assert_eq!(sum_amounts([]), "0");
+        assert_eq!(sum_amounts(["0", "0"]), "0");
+    }
+
+    #[test]
+    fn sum_amounts_is_exact_for_integers() {
+        assert_eq!(sum_amounts(["1500000", "2500000"]), "4000000");
+        // above u128 and f64 precision
+        let wei = "1000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            sum_amounts([wei, wei]),
+            "2000000000000000000000000000000000000000000000000000000000002"
+        );
+    }
+
+    #[test]
+    fn sum_amounts_of_decimal_coins() {
+        assert_eq!(sum_amounts(["0.5", "1.25"]), "1.75");
+        assert_eq!(sum_amounts(["0.5", "2"]), "2.5");
+    }
+
+    #[test]
+    fn sum_amounts_falls_back_to_f64_on_overflow() {
+        let max = U256::MAX.to_string();
+        let sum: f64 = sum_amounts([max.as_str(), "1"]).parse().unwrap();
+        assert!(sum > 1.15e77, "{}", sum);
+
+        // a single value beyond U256
+        let sum: f64 = sum_amounts([format!("{}0", max).as_str()]).parse().unwrap();
+        assert!(sum > 1.15e78, "{}", sum);
+    }
+}
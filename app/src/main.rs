@@ -1,35 +1,45 @@
 use anyhow::Result;
-use tokio;
 
-use arz_axum_for_services::config::{AppConfig, AppMode};
-use arz_axum_for_services::tasks::fetch_loop::{
-    run_btc_loop,
-    run_eth_loop,
-    run_bsc_loop,
-    run_tron_loop
-};
+use arz_axum_for_services::config::AppConfig;
+use arz_axum_for_services::router::build_router;
+use arz_axum_for_services::services::shutdown;
+use arz_axum_for_services::state::AppState;
+use arz_axum_for_services::tasks::supervisor::supervise_chain;
 
 #[tokio::main]
 async fn main() -> Result<()> {
 
-    //Testing Evn inputs
-    //println!("{:?}", std::env::vars().collect::<Vec<_>>());
-    let config = AppConfig::from_env();
-
-    match config.mode {
-        AppMode::Btc => {
-            run_btc_loop(config).await?;
-        }
-        AppMode::Eth => {
-            run_eth_loop(config).await?;
-        }
-        AppMode::Bsc => {
-            run_bsc_loop(config).await?;
-        },
-        AppMode::Tron => {
-            run_tron_loop(config).await?;
-        }
-    }
+    let config = AppConfig::load()?;
+    println!("[CONFIG] {:?}", config);
+
+    // HTTP query API (runs next to the fetch loops)
+    let state = AppState::new(&config);
+    let sync_status = state.sync_status.clone();
+    let app = build_router(state);
+    let listener = tokio::net::TcpListener::bind(&config.http_listen_addr).await?;
+    println!("[API] Listening on {}", config.http_listen_addr);
+
+    // Ctrl-C / SIGTERM: loops finish their current block, then the server stops
+    tokio::spawn(shutdown::listen_for_signals());
+
+    let server = async {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown::requested())
+            .await?;
+        Ok::<(), anyhow::Error>(())
+    };
+
+    // one independent task per chain; a failing chain is restarted on its own
+    let fetcher = async {
+        let loops = config
+            .fetched_chains()
+            .map(|chain| supervise_chain(chain.clone(), config.clone(), sync_status.clone()));
+        futures::future::join_all(loops).await;
+        Ok::<(), anyhow::Error>(())
+    };
+
+    // API keeps serving after the fetch loops finish; a server error stops the process
+    tokio::try_join!(server, fetcher)?;
 
     Ok(())
-}
\ No newline at end of file
+}
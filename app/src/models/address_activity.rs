@@ -0,0 +1,15 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// one native transaction or token transfer touching an address
+#[derive(Debug, Serialize, Deserialize, Row)]
+pub struct AddressActivityRow {
+    pub kind: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub seq: u32, // 0 = native tx, log_index + 1 = token transfer
+    pub from_addr: String,
+    pub to_addr: String,
+    pub asset: String,
+    pub amount: String,
+}
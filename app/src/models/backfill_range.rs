@@ -0,0 +1,19 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// One shard of a sharded backfill; blocks range_start..next_block are fully indexed
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct BackfillRangeRow {
+    pub chain: String,
+    // unix time the shards were planned at
+    pub plan: u64,
+    pub range_start: u64,
+    pub range_end: u64,
+    pub next_block: u64,
+}
+
+impl BackfillRangeRow {
+    pub fn is_done(&self) -> bool {
+        self.next_block > self.range_end
+    }
+}
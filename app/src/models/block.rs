@@ -0,0 +1,12 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// Record of a fully indexed block: parent_hash detects EVM reorgs, tx_count gaps
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct BlockRow {
+    pub number: u64,
+    pub hash: String,
+    pub parent_hash: String,
+    pub timestamp: u64,
+    pub tx_count: Option<u64>,
+}
@@ -5,6 +5,12 @@ pub struct BlockTx {
     pub txid: String,
     pub vin: Vec<Vin>,
     pub vout: Vec<Vout>,
+    pub status: Option<TxStatus>,
+}
+
+#[derive(Deserialize)]
+pub struct TxStatus {
+    pub block_time: Option<i64>,
 }
 
 #[derive(Deserialize)]
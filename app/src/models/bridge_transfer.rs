@@ -0,0 +1,17 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// Funds the tx sender moved into a bridge contract on this chain. The asset is a token
+// address or the native symbol, the amount a raw integer. bridge_name is set for contracts
+// from the chain's `bridges` registry, event_sig (topic0 hex) when the bridge emitted one.
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct BridgeTransferRow {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub user_addr: String,
+    pub asset: String,
+    pub amount: String,
+    pub bridge_contract: String,
+    pub bridge_name: String,
+    pub event_sig: String,
+}
@@ -1,7 +1,7 @@
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Row, Serialize)]
+#[derive(Debug, Row, Serialize, Deserialize)]
 pub struct ContractCallRow {
     pub tx_hash: String,
     pub contract_address: String,
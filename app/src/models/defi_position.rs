@@ -0,0 +1,33 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// One stake / deposit / withdraw of the tx sender with a protocol contract. asset is the
+// underlying paid in or taken out, receipt_token the token minted or burned for it
+// (stETH, aToken, cToken, vault share; empty when none). Raw integer amounts.
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct DefiPositionRow {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub user_addr: String,
+    pub action: String, // stake | deposit | withdraw
+    pub protocol: String,
+    pub asset: String,
+    pub amount: String,
+    pub receipt_token: String,
+    pub receipt_amount: String,
+}
+
+// an address's entries with one protocol and asset summed up, still open:
+// stakes and deposits minus withdraws
+#[derive(Debug, Serialize, Deserialize, Row)]
+pub struct OpenDefiPositionRow {
+    pub protocol: String,
+    pub asset: String,
+    pub amount: String,
+    pub receipt_token: String,
+    pub receipt_amount: String,
+    pub entries: u64,
+    pub exits: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+}
@@ -0,0 +1,18 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// Net result of a swap for the tx sender, one row per swap: assets_in is everything they
+// paid, assets_out everything they got, each with the raw amount at the same index.
+// Assets are token addresses or the native symbol, sorted within a side.
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct DexSwapRow {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub user_addr: String,
+    pub assets_in: Vec<String>,
+    pub amounts_in: Vec<String>,
+    pub assets_out: Vec<String>,
+    pub amounts_out: Vec<String>,
+    // contract the tx called: a router, or the pool itself
+    pub router: String,
+}
@@ -0,0 +1,33 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// LP tokens minted to (add) or burned by (remove) the tx sender, with the pair's tokens
+// in address order and raw amounts
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
+pub struct LiquidityEventRow {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub user_addr: String,
+    pub action: String, // add | remove
+    pub pool: String,
+    pub token0: String,
+    pub token1: String,
+    pub amount0: String,
+    pub amount1: String,
+    pub lp_amount: String,
+}
+
+// an address's events in one pool summed up: adds minus removes
+#[derive(Debug, Serialize, Deserialize, Row)]
+pub struct LiquidityPositionRow {
+    pub pool: String,
+    pub token0: String,
+    pub token1: String,
+    pub lp_amount: String,
+    pub amount0: String,
+    pub amount1: String,
+    pub adds: u64,
+    pub removes: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+}
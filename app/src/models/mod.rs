@@ -8,6 +8,14 @@ pub mod token_metadata;
 pub mod sync_state;
 pub mod contract_call;
 pub mod money_flow;
+pub mod token_balance;
+pub mod address_activity;
+pub mod block;
+pub mod backfill_range;
+pub mod dex_swap;
+pub mod liquidity_event;
+pub mod bridge_transfer;
+pub mod defi_position;
 
 // Structs for ClickHouse
 pub use wallet::WalletRow;
@@ -18,6 +26,14 @@ pub use token_metadata::TokenMetadataRow;
 pub use sync_state::SyncStateRow;
 pub use contract_call::ContractCallRow;
 pub use money_flow::MoneyFlowRow;
+pub use token_balance::TokenBalanceRow;
+pub use address_activity::AddressActivityRow;
+pub use block::BlockRow;
+pub use backfill_range::BackfillRangeRow;
+pub use dex_swap::DexSwapRow;
+pub use liquidity_event::{LiquidityEventRow, LiquidityPositionRow};
+pub use bridge_transfer::BridgeTransferRow;
+pub use defi_position::{DefiPositionRow, OpenDefiPositionRow};
 
 
 
@@ -1,11 +1,13 @@
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Row, Serialize)]
+#[derive(Debug, Row, Serialize, Deserialize)]
 pub struct MoneyFlowRow {
     pub tx_hash: String,
     pub from_addr: String,
     pub to_addr: String,
     pub amount: String,
     pub asset: String,
+    // sent into a bridge contract, leaving the chain
+    pub cross_chain_exit: bool,
 }
\ No newline at end of file
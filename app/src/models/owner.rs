@@ -1,7 +1,7 @@
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Row, Serialize)]
+#[derive(Debug, Row, Serialize, Deserialize)]
 pub struct OwnerRow {
     pub address: String,
     pub person_name: String,
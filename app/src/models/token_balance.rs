@@ -0,0 +1,11 @@
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+
+// address_token_balance joined with token_metadata
+#[derive(Debug, Serialize, Deserialize, Row)]
+pub struct TokenBalanceRow {
+    pub token_address: String,
+    pub balance: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
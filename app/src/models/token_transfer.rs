@@ -1,8 +1,8 @@
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 
-#[derive(Debug, Serialize, Row)]
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
 pub struct TokenTransferRow {
     pub tx_hash: String,
     pub block_number: u64,
@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 use clickhouse::Row;
 
-#[derive(Serialize, Row)]
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
 pub struct TransactionRow {
     pub hash: String,
     pub block_number: u64,
@@ -9,6 +9,9 @@ pub struct TransactionRow {
     pub to_addr: String,
     pub value: String,
     pub sensivity: u8,
+    // TxCategory code and receipt status, EVM only
+    pub category: Option<u8>,
+    pub status: Option<u8>,
 }
 
 #[repr(u8)]
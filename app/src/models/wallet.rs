@@ -1,7 +1,7 @@
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Row)]
+#[derive(Debug, Serialize, Deserialize, Row)]
 pub struct WalletRow {
     pub address: String,
     pub balance: String,
@@ -1,8 +1,17 @@
 use axum::{Router, routing::get};
-use crate::handlers::{health, status};
+use crate::handlers::{addresses, blocks, health, metrics, status, stream, transactions};
+use crate::state::AppState;
 
-pub fn build_router() -> Router {
+pub fn build_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health::health_check))
         .route("/status", get(status::status))
+        .route("/metrics", get(metrics::metrics))
+        .route("/v1/stream", get(stream::stream))
+        .route("/v1/{chain}/block/{number}", get(blocks::get_block))
+        .route("/v1/{chain}/tx/{hash}", get(transactions::get_tx))
+        .route("/v1/{chain}/address/{addr}", get(addresses::get_address))
+        .route("/v1/{chain}/address/{addr}/transactions", get(addresses::get_address_transactions))
+        .route("/v1/{chain}/address/{addr}/liquidity", get(addresses::get_address_liquidity))
+        .with_state(state)
 }
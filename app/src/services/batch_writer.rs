@@ -0,0 +1,258 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clickhouse::Client;
+use clickhouse::RowOwned;
+use clickhouse::RowWrite;
+
+use crate::models::{
+    BlockRow,
+    BridgeTransferRow,
+    ContractCallRow,
+    DefiPositionRow,
+    DexSwapRow,
+    LiquidityEventRow,
+    MoneyFlowRow,
+    OwnerRow,
+    TokenMetadataRow,
+    TokenTransferRow,
+    TransactionRow,
+    WalletRow,
+};
+use crate::services::live_feed::{self, FeedEvent};
+use crate::services::metrics;
+
+/// When a table buffer is inserted before the block boundary.
+#[derive(Debug, Clone)]
+pub struct WriterOptions {
+    pub max_rows: usize,
+    // age of the oldest buffered row
+    pub flush_interval: Duration,
+}
+
+struct Pending<T> {
+    rows: Vec<T>,
+    since: Option<Instant>,
+}
+
+impl<T> Pending<T> {
+    fn is_full(&self, options: &WriterOptions) -> bool {
+        self.rows.len() >= options.max_rows
+            || self.since.is_some_and(|since| since.elapsed() >= options.flush_interval)
+    }
+}
+
+/// Rows of one table waiting for a single multi-row INSERT.
+pub struct TableBuffer<T> {
+    table: &'static str,
+    pending: Mutex<Pending<T>>,
+    // one INSERT per table at a time; writers that fill the buffer meanwhile queue here
+    inserting: tokio::sync::Mutex<()>,
+    // runs with the rows once they are persisted (live feed)
+    on_flush: Option<fn(&str, Vec<T>)>,
+}
+
+impl<T> TableBuffer<T>
+where
+    T: RowOwned + RowWrite + Send,
+{
+    fn new(table: &'static str, on_flush: Option<fn(&str, Vec<T>)>) -> Self {
+        Self {
+            table,
+            pending: Mutex::new(Pending { rows: vec![], since: None }),
+            inserting: tokio::sync::Mutex::new(()),
+            on_flush,
+        }
+    }
+
+    /// Takes the buffered rows; unless `force`, only once the buffer is full.
+    fn take(&self, ctx: &WriterContext, force: bool) -> Vec<T> {
+        let mut pending = self.pending.lock().unwrap();
+        if !force && !pending.is_full(&ctx.options) {
+            return vec![];
+        }
+
+        pending.since = None;
+        metrics::set_pending_rows(&ctx.chain, self.table, 0);
+        mem::take(&mut pending.rows)
+    }
+
+    /// First match among the rows not yet handed to an INSERT.
+    pub fn find_map<R>(&self, f: impl FnMut(&T) -> Option<R>) -> Option<R> {
+        self.pending.lock().unwrap().rows.iter().find_map(f)
+    }
+
+    /// Buffers `row`. The writer that fills the buffer inserts it before returning,
+    /// so a slow ClickHouse holds the fetchers back instead of growing memory.
+    async fn push(&self, ctx: &WriterContext, row: T) -> Result<()> {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.rows.push(row);
+            pending.since.get_or_insert_with(Instant::now);
+
+            metrics::set_pending_rows(&ctx.chain, self.table, pending.rows.len());
+            if !pending.is_full(&ctx.options) {
+                return Ok(());
+            }
+        }
+
+        let started = Instant::now();
+        self.insert_pending(ctx, false).await?;
+        metrics::observe_writer_wait(&ctx.chain, self.table, started.elapsed());
+        Ok(())
+    }
+
+    async fn flush(&self, ctx: &WriterContext) -> Result<()> {
+        self.insert_pending(ctx, true).await
+    }
+
+    async fn insert_pending(&self, ctx: &WriterContext, force: bool) -> Result<()> {
+        // rows are only taken under this lock, so once it is held no earlier batch is in flight
+        let _inserting = self.inserting.lock().await;
+
+        let batch = self.take(ctx, force);
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let inserted = metrics::insert(self.table, async {
+            let mut insert = ctx.clickhouse.insert::<T>(self.table).await?;
+            for row in &batch {
+                insert.write(row).await?;
+            }
+            insert.end().await
+        })
+        .await;
+
+        if let Err(err) = inserted {
+            // keep the rows for the next flush: the block they belong to isn't committed yet
+            self.restore(ctx, batch);
+            return Err(err.into());
+        }
+        metrics::record_rows_written(self.table, batch.len());
+
+        if let Some(on_flush) = self.on_flush {
+            on_flush(&ctx.chain, batch);
+        }
+        Ok(())
+    }
+
+    fn restore(&self, ctx: &WriterContext, mut batch: Vec<T>) {
+        let mut pending = self.pending.lock().unwrap();
+        batch.append(&mut pending.rows);
+        pending.rows = batch;
+        pending.since.get_or_insert_with(Instant::now);
+        metrics::set_pending_rows(&ctx.chain, self.table, pending.rows.len());
+    }
+}
+
+struct WriterContext {
+    chain: String,
+    clickhouse: Arc<Client>,
+    options: WriterOptions,
+}
+
+/// Buffered writes of one chain's fetch loop: one multi-row INSERT per table instead of
+/// one per row. The driver calls `flush` at every block boundary before `sync_state` (or a
+/// backfill checkpoint) advances, so neither ever points past rows that are still buffered.
+/// Rows buffered when the loop fails are dropped and re-indexed from sync_state.
+pub struct BatchWriter {
+    ctx: WriterContext,
+    pub transactions: TableBuffer<TransactionRow>,
+    pub token_transfers: TableBuffer<TokenTransferRow>,
+    pub wallets: TableBuffer<WalletRow>,
+    pub owners: TableBuffer<OwnerRow>,
+    pub token_metadata: TableBuffer<TokenMetadataRow>,
+    pub blocks: TableBuffer<BlockRow>,
+    pub contract_calls: TableBuffer<ContractCallRow>,
+    pub money_flows: TableBuffer<MoneyFlowRow>,
+    pub dex_swaps: TableBuffer<DexSwapRow>,
+    pub liquidity_events: TableBuffer<LiquidityEventRow>,
+    pub bridge_transfers: TableBuffer<BridgeTransferRow>,
+    pub defi_positions: TableBuffer<DefiPositionRow>,
+}
+
+fn publish_transactions(chain: &str, rows: Vec<TransactionRow>) {
+    for row in rows {
+        live_feed::publish(FeedEvent::Transaction {
+            chain: chain.to_string(),
+            row,
+        });
+    }
+}
+
+fn publish_token_transfers(chain: &str, rows: Vec<TokenTransferRow>) {
+    for row in rows {
+        live_feed::publish(FeedEvent::TokenTransfer {
+            chain: chain.to_string(),
+            row,
+        });
+    }
+}
+
+impl BatchWriter {
+    pub fn new(chain: &str, clickhouse: Arc<Client>, options: WriterOptions) -> Self {
+        Self {
+            ctx: WriterContext {
+                chain: chain.to_string(),
+                clickhouse,
+                options,
+            },
+            transactions: TableBuffer::new("transactions", Some(publish_transactions)),
+            token_transfers: TableBuffer::new("token_transfers", Some(publish_token_transfers)),
+            wallets: TableBuffer::new("wallet_info", None),
+            owners: TableBuffer::new("owner_info", None),
+            token_metadata: TableBuffer::new("token_metadata", None),
+            blocks: TableBuffer::new("blocks", None),
+            contract_calls: TableBuffer::new("contract_calls", None),
+            money_flows: TableBuffer::new("money_flows", None),
+            dex_swaps: TableBuffer::new("dex_swaps", None),
+            liquidity_events: TableBuffer::new("liquidity_events", None),
+            bridge_transfers: TableBuffer::new("bridge_transfers", None),
+            defi_positions: TableBuffer::new("defi_positions", None),
+        }
+    }
+
+    pub fn chain(&self) -> &str {
+        &self.ctx.chain
+    }
+
+    /// Age at which buffered rows are inserted; backfill checkpoints are saved at this pace.
+    pub fn flush_interval(&self) -> Duration {
+        self.ctx.options.flush_interval
+    }
+
+    /// Client bound to the chain's database, for the reads done while indexing.
+    pub fn clickhouse(&self) -> &Arc<Client> {
+        &self.ctx.clickhouse
+    }
+
+    pub async fn write<T>(&self, buffer: &TableBuffer<T>, row: T) -> Result<()>
+    where
+        T: RowOwned + RowWrite + Send,
+    {
+        buffer.push(&self.ctx, row).await
+    }
+
+    /// Inserts everything buffered so far.
+    pub async fn flush(&self) -> Result<()> {
+        let ctx = &self.ctx;
+        tokio::try_join!(
+            self.transactions.flush(ctx),
+            self.token_transfers.flush(ctx),
+            self.wallets.flush(ctx),
+            self.owners.flush(ctx),
+            self.token_metadata.flush(ctx),
+            self.blocks.flush(ctx),
+            self.contract_calls.flush(ctx),
+            self.money_flows.flush(ctx),
+            self.dex_swaps.flush(ctx),
+            self.liquidity_events.flush(ctx),
+            self.bridge_transfers.flush(ctx),
+            self.defi_positions.flush(ctx),
+        )?;
+        Ok(())
+    }
+}
@@ -1,14 +1,19 @@
+use crate::services::batch_writer::BatchWriter;
+use crate::services::indexer::{BlockHeader, ChainIndexer};
 use crate::services::loader::LoaderBtc;
-use crate::models::transaction::Sensivity;
+use crate::services::metrics;
+use crate::services::sync_status::SyncStatus;
+use crate::models::transaction::{Sensivity, TransactionRow};
 use crate::services::progress::{save_tx, save_wallet};
 use crate::models::blockstreams::*;
 
-use crate::db::sync_state::{get_last_synced_block, update_last_synced_block};
+use crate::db::init_btc::init_btc_db;
+use crate::helper::rpc_pool::RpcPool;
 
 use clickhouse::Client;
 use std::sync::Arc;
 use anyhow::Result;
-use futures::stream::{FuturesUnordered, StreamExt};
+use chrono::DateTime;
 
 // helper functions ---------------
 fn calc_sensivity_btc(value: f64) -> Sensivity {
@@ -26,68 +31,80 @@ fn btc_from_sats(sats: u64) -> f64 {
 }
 // --------------------------------
 
-pub async fn fetch_btc(
-    loader: Arc<LoaderBtc>,
-    start_block: u64,
-    total_txs: u64,
-    base_url: &str,
-) -> Result<()> {
-    let clickhouse = loader.clickhouse.clone();
-
-    // latest block واقعی شبکه
-    let latest_height = get_latest_btc_height(base_url).await?;
-    println!("BTC latest height: {}", latest_height);
+/// A block's hash together with all of its transactions (Esplora has no single call for both).
+pub struct BtcBlock {
+    hash: String,
+    txs: Vec<BlockTx>,
+}
 
-    // ادامه از sync_state
-    let last_synced = get_last_synced_block(&clickhouse, "btc").await?;
-    let mut current_height = last_synced.unwrap_or(start_block);
+impl ChainIndexer for LoaderBtc {
+    type Block = BtcBlock;
+    type Tx = BlockTx;
+    type TxOutput = ();
 
-    let mut tx_count: u64 = 0;
+    fn chain(&self) -> &str {
+        "btc"
+    }
 
-    while current_height <= latest_height {
-        if tx_count >= total_txs {
-            break;
-        }
+    fn clickhouse(&self) -> &Arc<Client> {
+        &self.clickhouse
+    }
 
-        println!("BTC processing block: {}", current_height);
+    fn writer(&self) -> &Arc<BatchWriter> {
+        &self.writer
+    }
 
-        let block_hash = get_block_hash_by_height(base_url, current_height).await?;
-        let txs = get_block_txs(base_url, &block_hash).await?;
+    fn sync_status(&self) -> &Arc<SyncStatus> {
+        &self.sync_status
+    }
 
-        let mut tasks = FuturesUnordered::new();
+    async fn init_db(&self, admin: &Client) -> Result<()> {
+        init_btc_db(admin, &self.database).await
+    }
 
-        for tx in txs {
-            if tx_count >= total_txs {
-                break;
-            }
+    async fn head(&self) -> Result<u64> {
+        get_latest_btc_height(&self.pool).await
+    }
 
-            let clickhouse = Arc::clone(&clickhouse);
+    async fn confirmed_head(&self, head: u64) -> Result<u64> {
+        Ok(self.finality.confirmed_head(head))
+    }
 
-            tasks.push(tokio::spawn(async move {
-                process_tx(clickhouse, tx, current_height).await?;
-                Ok::<(), anyhow::Error>(())
-            }));
+    async fn fetch_block(&self, number: u64) -> Result<Option<BtcBlock>> {
+        println!("BTC processing block: {}", number);
 
-            tx_count += 1;
-            println!("Added BTC tx #{}", tx_count);
-        }
+        let hash = get_block_hash_by_height(&self.pool, number).await?;
+        let txs = get_block_txs(&self.pool, &hash).await?;
+        Ok(Some(BtcBlock { hash, txs }))
+    }
 
-        while let Some(res) = tasks.next().await {
-            res??;
+    fn header(&self, block: &BtcBlock) -> BlockHeader {
+        BlockHeader {
+            hash: block.hash.clone(),
+            parent_hash: String::new(),
+            time: block
+                .txs
+                .first()
+                .and_then(|tx| tx.status.as_ref()?.block_time)
+                .and_then(|t| DateTime::from_timestamp(t, 0)),
         }
+    }
 
-        // بعد از کامل شدن بلاک، sync_state رو update کن
-        update_last_synced_block(&clickhouse, "btc", current_height).await?;
-        println!("BTC synced block: {}", current_height);
-
-        current_height += 1;
+    fn block_txs(&self, block: BtcBlock) -> Vec<BlockTx> {
+        block.txs
     }
 
-    Ok(())
+    fn process_tx(
+        self: Arc<Self>,
+        tx: BlockTx,
+        block_number: u64,
+    ) -> impl Future<Output = Result<()>> + Send + 'static {
+        process_tx(self.writer.clone(), tx, block_number)
+    }
 }
 
 async fn process_tx(
-    clickhouse: Arc<Client>,
+    writer: Arc<BatchWriter>,
     tx: BlockTx,
     block_number: u64,
 ) -> Result<()> {
@@ -112,19 +129,23 @@ async fn process_tx(
     let total_value = btc_from_sats(total_value_sats);
 
     save_tx(
-        clickhouse.clone(),
-        tx.txid.clone(),
-        block_number,
-        from_addr.clone(),
-        to_addr.clone(),
-        total_value.to_string(),
-        calc_sensivity_btc(total_value) as u8,
+        &writer,
+        TransactionRow {
+            hash: tx.txid.clone(),
+            block_number,
+            from_addr: from_addr.clone(),
+            to_addr: to_addr.clone(),
+            value: total_value.to_string(),
+            sensivity: calc_sensivity_btc(total_value) as u8,
+            category: None,
+            status: None,
+        },
     )
     .await?;
 
     // در BTC nonce نداریم → صفر
     save_wallet(
-        clickhouse.clone(),
+        &writer,
         &from_addr,
         total_value.to_string(),
         0,
@@ -133,7 +154,7 @@ async fn process_tx(
     .await?;
 
     save_wallet(
-        clickhouse.clone(),
+        &writer,
         &to_addr,
         total_value.to_string(),
         0,
@@ -146,29 +167,38 @@ async fn process_tx(
 
 // API Helper ------------------------------
 
-async fn get_latest_btc_height(base_url: &str) -> Result<u64> {
-    let url = format!("{}/blocks/tip/height", base_url);
-    let text = reqwest::get(&url).await?.text().await?;
+// GET `path` on one of the pooled APIs, failing over to another on errors
+async fn api_get(pool: &RpcPool, method: &str, path: &str) -> Result<String> {
+    let text = metrics::rpc(
+        "btc",
+        method,
+        pool.send(method, |http, base_url| http.get(format!("{}{}", base_url, path))),
+    )
+    .await?;
+    Ok(text)
+}
+
+pub async fn get_latest_btc_height(pool: &RpcPool) -> Result<u64> {
+    let text = api_get(pool, "blocks_tip_height", "/blocks/tip/height").await?;
     Ok(text.trim().parse::<u64>()?)
 }
 
-async fn get_block_hash_by_height(base_url: &str, height: u64) -> Result<String> {
-    let url = format!("{}/block-height/{}", base_url, height);
-    Ok(reqwest::get(&url).await?.text().await?.trim().to_string())
+async fn get_block_hash_by_height(pool: &RpcPool, height: u64) -> Result<String> {
+    let text = api_get(pool, "block_height", &format!("/block-height/{}", height)).await?;
+    Ok(text.trim().to_string())
 }
 
-async fn get_block_txs(base_url: &str, block_hash: &str) -> Result<Vec<BlockTx>> {
+async fn get_block_txs(pool: &RpcPool, block_hash: &str) -> Result<Vec<BlockTx>> {
     let mut all_txs = Vec::new();
     let mut last_seen_txid: Option<String> = None;
 
     loop {
-        let url = match &last_seen_txid {
-            Some(txid) => format!("{}/block/{}/txs/{}", base_url, block_hash, txid),
-            None => format!("{}/block/{}/txs", base_url, block_hash),
+        let path = match &last_seen_txid {
+            Some(txid) => format!("/block/{}/txs/{}", block_hash, txid),
+            None => format!("/block/{}/txs", block_hash),
         };
 
-        let resp = reqwest::get(&url).await?;
-        let body_text = resp.text().await?;
+        let body_text = api_get(pool, "block_txs", &path).await?;
 
         let txs: Vec<BlockTx> = serde_json::from_str(&body_text)?;
 
@@ -3,26 +3,78 @@ use ethers::prelude::*;
 use ethers::utils::keccak256;
 use ethers::types::{Address, U256};
 
-#[derive(Debug, Clone)]
+use crate::config::BridgeContract;
+
+// stored as `transactions.category`, so the codes must not change
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TxCategory {
-    Failed, // 1
-    Approve, // 2
-    NFTTransfer, // 3
-    Swap, // 4
-    LiquidityPool, // 5
-    Bridge, // 6
-    Stake, // 7
-    ERC20Transfer, // 8
-    EthTransfer, // 9
-    Other, // 10
+    Failed = 1,
+    Approve = 2,
+    NFTTransfer = 3,
+    Swap = 4,
+    LiquidityPool = 5,
+    Bridge = 6,
+    Stake = 7,
+    ERC20Transfer = 8,
+    EthTransfer = 9,
+    Other = 10,
+}
+
+impl TxCategory {
+    pub const ALL: [TxCategory; 10] = [
+        TxCategory::Failed,
+        TxCategory::Approve,
+        TxCategory::NFTTransfer,
+        TxCategory::Swap,
+        TxCategory::LiquidityPool,
+        TxCategory::Bridge,
+        TxCategory::Stake,
+        TxCategory::ERC20Transfer,
+        TxCategory::EthTransfer,
+        TxCategory::Other,
+    ];
+
+    /// Name used by the API.
+    pub fn name(self) -> &'static str {
+        match self {
+            TxCategory::Failed => "failed",
+            TxCategory::Approve => "approve",
+            TxCategory::NFTTransfer => "nft_transfer",
+            TxCategory::Swap => "swap",
+            TxCategory::LiquidityPool => "liquidity_pool",
+            TxCategory::Bridge => "bridge",
+            TxCategory::Stake => "stake",
+            TxCategory::ERC20Transfer => "erc20_transfer",
+            TxCategory::EthTransfer => "eth_transfer",
+            TxCategory::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.name() == name)
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| *category as u8 == code)
+    }
 }
 
-pub fn classify_tx(tx: &Transaction, receipt: &TransactionReceipt) -> TxCategory {
+pub fn classify_tx(
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+    bridges: &BridgeRegistry,
+) -> TxCategory {
 
     if is_failed(receipt) {
         return TxCategory::Failed;
     }
 
+    // funds sent into a known bridge leave the chain, whatever else the tx did on the way
+    if extract_registered_bridge(tx, receipt, bridges).is_some() {
+        return TxCategory::Bridge;
+    }
+
     // --- Layer 1 (Hard) ---
     if is_approve(tx, receipt) {
         return TxCategory::Approve;
@@ -78,6 +130,13 @@ fn approve_sig() -> H256 {
     H256::from(keccak256("Approval(address,address,uint256)"))
 }
 
+// amount as the last 32-byte word of the log data: the value of every event matched here,
+// and U256::from_big_endian panics on longer input (non-standard contracts emit anything)
+fn log_amount(log: &Log) -> U256 {
+    let data = &log.data.0;
+    U256::from_big_endian(&data[data.len().saturating_sub(32)..])
+}
+
 // --------------- Failed -----------------
 
 pub fn is_failed(receipt: &TransactionReceipt) -> bool {
@@ -97,9 +156,12 @@ pub fn is_nft_transfer(receipt: &TransactionReceipt) -> bool {
     ));
 
     receipt.logs.iter().any(|log| {
-        (log.topics.len() == 4 && log.topics[0] == erc721_sig)
-            || log.topics[0] == erc1155_single
-            || log.topics[0] == erc1155_batch
+        let Some(&topic0) = log.topics.first() else {
+            return false;
+        };
+        (log.topics.len() == 4 && topic0 == erc721_sig)
+            || topic0 == erc1155_single
+            || topic0 == erc1155_batch
     })
 }
 
@@ -141,7 +203,7 @@ fn collect_token_flows(
 
         let from = Address::from_slice(&log.topics[1][12..]);
         let to   = Address::from_slice(&log.topics[2][12..]);
-        let amount = U256::from_big_endian(&log.data.0);
+        let amount = log_amount(log);
         let token  = log.address;
 
         if from == user {
@@ -156,6 +218,14 @@ fn collect_token_flows(
     flow
 }
 
+// the pair's two tokens sorted by address (the Uniswap token0 / token1 order), so adds and
+// removes of the same pool line up
+fn token_pair(amounts: &HashMap<Address, U256>) -> ((Address, U256), (Address, U256)) {
+    let mut pair: Vec<(Address, U256)> = amounts.iter().map(|(&token, &amount)| (token, amount)).collect();
+    pair.sort_by_key(|&(token, _)| token);
+    (pair[0], pair[1])
+}
+
 fn detect_lp_add(
     flow: &TokenFlow,
 ) -> Option<LiquidityPoolDetails> {
@@ -168,9 +238,7 @@ fn detect_lp_add(
     let (&lp_token, &lp_amount) =
         flow.received.iter().next().unwrap();
 
-    let mut sent_iter = flow.sent.iter();
-    let (&token0, &amount0) = sent_iter.next().unwrap();
-    let (&token1, &amount1) = sent_iter.next().unwrap();
+    let ((token0, amount0), (token1, amount1)) = token_pair(&flow.sent);
 
     Some(LiquidityPoolDetails {
         action: LiquidityAction::Add,
@@ -195,9 +263,7 @@ fn detect_lp_remove(
     let (&lp_token, &lp_amount) =
         flow.sent.iter().next().unwrap();
 
-    let mut recv_iter = flow.received.iter();
-    let (&token0, &amount0) = recv_iter.next().unwrap();
-    let (&token1, &amount1) = recv_iter.next().unwrap();
+    let ((token0, amount0), (token1, amount1)) = token_pair(&flow.received);
 
     Some(LiquidityPoolDetails {
         action: LiquidityAction::Remove,
@@ -247,7 +313,32 @@ pub struct BridgeDetails {
     pub asset: BridgeAsset,
     pub amount: U256,
     pub bridge_contract: Address,
-    pub event_sig: H256,
+    // None for a registered bridge that emitted nothing itself
+    pub event_sig: Option<H256>,
+}
+
+/// Bridge contracts configured for the chain (`bridges`), with their names.
+#[derive(Debug, Clone, Default)]
+pub struct BridgeRegistry {
+    contracts: HashMap<Address, String>,
+}
+
+impl BridgeRegistry {
+    pub fn new(bridges: &[BridgeContract]) -> Self {
+        let contracts = bridges
+            .iter()
+            .map(|bridge| (bridge.address, bridge.name.clone()))
+            .collect();
+        Self { contracts }
+    }
+
+    pub fn name(&self, contract: &Address) -> Option<&str> {
+        self.contracts.get(contract).map(String::as_str)
+    }
+
+    fn contains(&self, contract: &Address) -> bool {
+        self.contracts.contains_key(contract)
+    }
 }
 
 const BRIDGE_EVENT_SIGNATURES: [&str; 5] = [
@@ -267,11 +358,11 @@ fn is_bridge_event(sig: H256) -> bool {
     })
 }
 
-fn find_bridge_event<'a>(
-    receipt: &'a TransactionReceipt,
-) -> Option<&'a ethers::types::Log> {
+fn find_bridge_event(
+    receipt: &TransactionReceipt,
+) -> Option<&ethers::types::Log> {
     receipt.logs.iter().find(|log| {
-        is_bridge_event(log.topics[0])
+        log.topics.first().is_some_and(|&topic0| is_bridge_event(topic0))
     })
 }
 
@@ -284,7 +375,7 @@ pub fn extract_bridge_details(
 
     let bridge_log = find_bridge_event(receipt)?;
     let bridge_contract = bridge_log.address;
-    let event_sig = bridge_log.topics[0];
+    let event_sig = bridge_log.topics.first().copied();
 
     let (asset, amount) =
         extract_bridge_asset(tx, receipt, user, bridge_contract)?;
@@ -312,7 +403,7 @@ fn extract_bridge_asset(
             let to   = Address::from_slice(&log.topics[2][12..]);
 
             if from == user && to == bridge_contract {
-                let amount = U256::from_big_endian(&log.data.0);
+                let amount = log_amount(log);
                 return Some((BridgeAsset::Erc20(log.address), amount));
             }
         }
@@ -325,6 +416,77 @@ fn extract_bridge_asset(
 
     None
 }
+
+// (from, to) of an ERC20 Transfer log
+fn transfer_parties(log: &Log) -> Option<(Address, Address)> {
+    if log.topics.len() != 3 || log.topics[0] != erc20_transfer_sig() {
+        return None;
+    }
+    Some((
+        Address::from_slice(&log.topics[1][12..]),
+        Address::from_slice(&log.topics[2][12..]),
+    ))
+}
+
+/// Transfer into a contract of the registry: tokens the sender sent straight to one, or
+/// a call to one with native value or tokens the sender moved elsewhere (e.g. a router
+/// escrowing them in a gateway).
+fn extract_registered_bridge(
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+    bridges: &BridgeRegistry,
+) -> Option<BridgeDetails> {
+    let user = tx.from;
+
+    let direct = receipt.logs.iter().find_map(|log| {
+        let (from, to) = transfer_parties(log)?;
+        (from == user && bridges.contains(&to))
+            .then(|| (to, BridgeAsset::Erc20(log.address), log_amount(log)))
+    });
+
+    let (bridge_contract, asset, amount) = match direct {
+        Some(found) => found,
+        None => {
+            let contract = tx.to.filter(|to| bridges.contains(to))?;
+            if tx.value > U256::zero() {
+                (contract, BridgeAsset::Eth, tx.value)
+            } else {
+                let log = receipt
+                    .logs
+                    .iter()
+                    .find(|log| transfer_parties(log).is_some_and(|(from, _)| from == user))?;
+                (contract, BridgeAsset::Erc20(log.address), log_amount(log))
+            }
+        }
+    };
+
+    let event_sig = receipt
+        .logs
+        .iter()
+        .filter(|log| log.address == bridge_contract)
+        .find_map(|log| log.topics.first().copied());
+
+    Some(BridgeDetails {
+        user,
+        asset,
+        amount,
+        bridge_contract,
+        event_sig,
+    })
+}
+
+/// Bridge transfer of a tx: a contract of the registry first, then any contract
+/// emitting one of the known bridge events.
+pub fn extract_bridge_transfer(
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+    bridges: &BridgeRegistry,
+) -> Option<BridgeDetails> {
+    extract_registered_bridge(tx, receipt, bridges)
+        .or_else(|| extract_bridge_details(tx, receipt))
+}
+
+// event signatures only; classify_tx checks the registry before the other detectors
 pub fn is_bridge(tx: &Transaction, receipt: &TransactionReceipt) -> bool {
     extract_bridge_details(tx, receipt).is_some()
 }
@@ -344,6 +506,9 @@ pub struct AssetAmount {
     pub amount: U256,
 }
 
+// (asset, amount) pair used by the stake / withdraw / deposit flow extractors
+type AssetFlow = (Asset, U256);
+
 #[derive(Debug, Clone)]
 pub struct SwapDetails {
     pub user: Address,
@@ -374,7 +539,7 @@ fn collect_balance_deltas(
         if log.topics.len() == 3 && log.topics[0] == erc20_transfer_sig() {
             let from = Address::from_slice(&log.topics[1][12..]);
             let to   = Address::from_slice(&log.topics[2][12..]);
-            let amount = U256::from_big_endian(&log.data.0);
+            let amount = log_amount(log);
             let token = log.address;
 
             if from == user {
@@ -391,16 +556,16 @@ fn collect_balance_deltas(
         }
 
         // -------- WETH unwrap --------
-        if log.topics[0] == weth_withdraw_sig() {
-            let amount = U256::from_big_endian(&log.data.0);
+        if log.topics.first() == Some(&weth_withdraw_sig()) {
+            let amount = log_amount(log);
             *delta.received
                 .entry(Asset::Eth)
                 .or_insert(U256::zero()) += amount;
         }
 
         // -------- WETH wrap --------
-        if log.topics[0] == weth_deposit_sig() {
-            let amount = U256::from_big_endian(&log.data.0);
+        if log.topics.first() == Some(&weth_deposit_sig()) {
+            let amount = log_amount(log);
             *delta.sent
                 .entry(Asset::Eth)
                 .or_insert(U256::zero()) += amount;
@@ -519,7 +684,7 @@ fn extract_erc20_transfers(
 
         let from = Address::from_slice(&log.topics[1][12..]);
         let to   = Address::from_slice(&log.topics[2][12..]);
-        let amount = U256::from_big_endian(&log.data.0);
+        let amount = log_amount(log);
 
         // mint / burn حذف
         if from == Address::zero() || to == Address::zero() {
@@ -600,8 +765,8 @@ fn has_weth_wrap_or_unwrap(
     let withdraw = weth_withdraw_sig();
 
     receipt.logs.iter().any(|log| {
-        log.topics.get(0) == Some(&deposit)
-            || log.topics.get(0) == Some(&withdraw)
+        log.topics.first() == Some(&deposit)
+            || log.topics.first() == Some(&withdraw)
     })
 }
 
@@ -673,7 +838,7 @@ fn extract_approve_events(
 
         let owner   = Address::from_slice(&log.topics[1][12..]);
         let spender = Address::from_slice(&log.topics[2][12..]);
-        let amount  = U256::from_big_endian(&log.data.0);
+        let amount  = log_amount(log);
 
         if owner != user {
             continue;
@@ -764,6 +929,18 @@ pub struct StakeDetails {
     pub received_amount: Option<U256>,
 }
 
+// token minted to `user` in the tx (stETH, aToken, cToken, vault share)
+fn minted_to(receipt: &TransactionReceipt, user: Address) -> Option<AssetFlow> {
+    receipt.logs.iter().find_map(|log| {
+        if log.topics.len() != 3 || log.topics[0] != erc20_transfer_sig() {
+            return None;
+        }
+        let from = Address::from_slice(&log.topics[1][12..]);
+        let to   = Address::from_slice(&log.topics[2][12..]);
+        (from == Address::zero() && to == user).then(|| (Asset::Erc20(log.address), log_amount(log)))
+    })
+}
+
 fn stake_method_selectors() -> Vec<[u8; 4]> {
     vec![
         keccak256("stake(uint256)")[0..4].try_into().unwrap(),
@@ -783,9 +960,7 @@ fn has_stake_intent(tx: &Transaction) -> bool {
 
     let selector: [u8; 4] = input[0..4].try_into().unwrap();
 
-    stake_method_selectors()
-        .iter()
-        .any(|s| *s == selector)
+    stake_method_selectors().contains(&selector)
 }
 fn extract_stake_flows(
     tx: &Transaction,
@@ -794,17 +969,17 @@ fn extract_stake_flows(
 ) -> Option<(
     Asset,              // sent_asset
     U256,               // sent_amount
-    Option<(Asset, U256)>, // received (optional)
+    Option<AssetFlow>,  // received (optional)
 )> {
     let staking_contract = tx.to?;
 
     // ---------------- ETH stake ----------------
-    
+    // receipt token minted for it, e.g. Lido's stETH
     if tx.value > U256::zero() {
         return Some((
             Asset::Eth,
             tx.value,
-            None,
+            minted_to(receipt, user),
         ));
     }
 
@@ -823,7 +998,7 @@ fn extract_stake_flows(
 
         let from = Address::from_slice(&log.topics[1][12..]);
         let to   = Address::from_slice(&log.topics[2][12..]);
-        let amount = U256::from_big_endian(&log.data.0);
+        let amount = log_amount(log);
         let token = log.address;
 
         // user -> staking contract  (دارایی staked)
@@ -855,6 +1030,11 @@ pub fn extract_stake_details(
     let user = tx.from;
     let staking_contract = tx.to?;
 
+    // intent must exist (checked first, it's the cheap part)
+    if !has_stake_intent(tx) {
+        return None;
+    }
+
     // hard exclusions 
     if is_swap(tx, receipt)
         || is_bridge(tx, receipt)
@@ -864,11 +1044,6 @@ pub fn extract_stake_details(
         return None;
     }
 
-    // intent must exist
-    if !has_stake_intent(tx) {
-        return None;
-    }
-
     let (sent_asset, sent_amount, received) =
         extract_stake_flows(tx, receipt, user)?;
 
@@ -920,9 +1095,7 @@ fn has_withdraw_intent(tx: &Transaction) -> bool {
 
     let selector: [u8; 4] = input[0..4].try_into().unwrap();
 
-    withdraw_method_selectors()
-        .iter()
-        .any(|s| *s == selector)
+    withdraw_method_selectors().contains(&selector)
 }
 fn extract_withdraw_flows(
     tx: &Transaction,
@@ -931,7 +1104,7 @@ fn extract_withdraw_flows(
 ) -> Option<(
     Asset,              // received_asset
     U256,               // received_amount
-    Option<(Asset, U256)>, // burned (receipt token)
+    Option<AssetFlow>,  // burned (receipt token)
 )> {
     let staking_contract = tx.to?;
 
@@ -942,8 +1115,8 @@ fn extract_withdraw_flows(
    
     for log in &receipt.logs {
 
-        if log.topics.get(0) == Some(&weth_withdraw_sig()) {
-            let amount = U256::from_big_endian(&log.data.0);
+        if log.topics.first() == Some(&weth_withdraw_sig()) {
+            let amount = log_amount(log);
             received = Some((Asset::Eth, amount));
         }
     }
@@ -959,7 +1132,7 @@ fn extract_withdraw_flows(
 
         let from = Address::from_slice(&log.topics[1][12..]);
         let to   = Address::from_slice(&log.topics[2][12..]);
-        let amount = U256::from_big_endian(&log.data.0);
+        let amount = log_amount(log);
         let token = log.address;
 
         // staking contract -> user (asset received)
@@ -990,6 +1163,11 @@ pub fn extract_withdraw_details(
     let user = tx.from;
     let staking_contract = tx.to?;
 
+    // intent required (checked first, it's the cheap part)
+    if !has_withdraw_intent(tx) {
+        return None;
+    }
+
     // ❌ hard exclusions
     if is_swap(tx, receipt)
         || is_bridge(tx, receipt)
@@ -999,11 +1177,6 @@ pub fn extract_withdraw_details(
         return None;
     }
 
-    // intent required
-    if !has_withdraw_intent(tx) {
-        return None;
-    }
-
     let (received_asset, received_amount, burned) =
         extract_withdraw_flows(tx, receipt, user)?;
 
@@ -1055,9 +1228,7 @@ fn has_deposit_intent(tx: &Transaction) -> bool {
 
     let selector: [u8; 4] = input[0..4].try_into().unwrap();
 
-    deposit_method_selectors()
-        .iter()
-        .any(|s| *s == selector)
+    deposit_method_selectors().contains(&selector)
 }
 fn extract_deposit_flows(
     tx: &Transaction,
@@ -1066,7 +1237,7 @@ fn extract_deposit_flows(
 ) -> Option<(
     Asset,
     U256,
-    Option<(Asset, U256)>,
+    Option<AssetFlow>,
 )> {
     let contract = tx.to?;
 
@@ -1075,7 +1246,7 @@ fn extract_deposit_flows(
         return Some((
             Asset::Eth,
             tx.value,
-            None,
+            minted_to(receipt, user),
         ));
     }
 
@@ -1092,7 +1263,7 @@ fn extract_deposit_flows(
 
         let from = Address::from_slice(&log.topics[1][12..]);
         let to   = Address::from_slice(&log.topics[2][12..]);
-        let amount = U256::from_big_endian(&log.data.0);
+        let amount = log_amount(log);
         let token = log.address;
 
         // user -> deposit contract
@@ -1119,6 +1290,11 @@ pub fn extract_deposit_details(
     let user = tx.from;
     let contract = tx.to?;
 
+    // intent required (checked first, it's the cheap part)
+    if !has_deposit_intent(tx) {
+        return None;
+    }
+
     // ❌ exclusions (خیلی مهم)
     if is_swap(tx, receipt)
         || is_bridge(tx, receipt)
@@ -1130,11 +1306,6 @@ pub fn extract_deposit_details(
         return None;
     }
 
-    // intent required
-    if !has_deposit_intent(tx) {
-        return None;
-    }
-
     let (asset, amount, received) =
         extract_deposit_flows(tx, receipt, user)?;
 
@@ -0,0 +1,574 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use anyhow::Result;
+use chrono::DateTime;
+use clickhouse::Client;
+use ethers::prelude::*;
+
+use crate::db::init_evm::init_evm_db;
+use crate::models::bridge_transfer::BridgeTransferRow;
+use crate::models::defi_position::DefiPositionRow;
+use crate::models::dex_swap::DexSwapRow;
+use crate::models::liquidity_event::LiquidityEventRow;
+use crate::models::money_flow::MoneyFlowRow;
+use crate::services::ethereum_detail::{
+    Asset,
+    AssetAmount,
+    BridgeAsset,
+    LiquidityAction,
+    TxCategory,
+    classify_tx,
+    extract_bridge_transfer,
+    extract_deposit_details,
+    extract_liquidity_pool,
+    extract_stake_details,
+    extract_swap_details,
+    extract_withdraw_details,
+};
+use crate::models::token_transfer::TokenTransferRow;
+use crate::models::transaction::{Sensivity, TransactionRow};
+use crate::services::finality::evm_confirmed_head;
+use crate::services::batch_writer::BatchWriter;
+use crate::services::indexer::{BlockHeader, ChainIndexer};
+use crate::services::loader::LoaderEvm;
+use crate::services::metrics;
+use crate::services::progress::{
+    save_bridge_transfer,
+    save_defi_position,
+    save_dex_swap,
+    save_liquidity_event,
+    save_money_flow,
+    save_token_transfer,
+    save_tx,
+    save_wallet,
+};
+use crate::services::sync_status::SyncStatus;
+use crate::services::token_metadata_worker;
+
+// JSON-RPC "method not found"
+const METHOD_NOT_FOUND: i64 = -32601;
+
+const ERC20_TRANSFER_TOPIC: &str =
+    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+/// A block with its transactions and all of their receipts.
+pub struct EvmBlock {
+    block: Block<Transaction>,
+    receipts: HashMap<H256, TransactionReceipt>,
+}
+
+pub struct EvmTx {
+    tx: Transaction,
+    receipt: TransactionReceipt,
+}
+
+/// Addresses `after_block` handles once per block instead of once per tx.
+pub struct EvmTxOutput {
+    tokens: Vec<Address>,
+    wallets: Vec<Address>,
+}
+
+impl LoaderEvm {
+    fn calc_sensivity(&self, value_wei: U256) -> Sensivity {
+        let value = value_wei.as_u128() as f64 / 10f64.powi(self.params.decimals as i32);
+
+        if value > self.params.sensivity_red {
+            Sensivity::Red
+        } else if value > self.params.sensivity_yellow {
+            Sensivity::Yellow
+        } else {
+            Sensivity::Green
+        }
+    }
+
+    /// Token address, or the native symbol for the chain's coin.
+    fn asset_name(&self, asset: &Asset) -> String {
+        match asset {
+            Asset::Eth => self.params.native_symbol.clone(),
+            Asset::Erc20(token) => format!("{:#x}", token),
+        }
+    }
+
+    /// All receipts of `block` keyed by tx hash: one eth_getBlockReceipts call, or batched
+    /// eth_getTransactionReceipt on nodes that don't serve it.
+    async fn block_receipts(
+        &self,
+        number: u64,
+        block: &Block<Transaction>,
+    ) -> Result<HashMap<H256, TransactionReceipt>> {
+        if block.transactions.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let chain = self.chain.as_str();
+        let mut receipts = None;
+
+        if self.block_receipts_supported.load(Ordering::Relaxed) {
+            let _permit = metrics::acquire(chain, &self.rpc_limiter).await?;
+            match metrics::rpc(chain, "get_block_receipts", self.provider.get_block_receipts(number)).await {
+                Ok(list) => receipts = Some(list),
+                // the node answered but can't serve it; fall back for this block
+                Err(err) => match RpcError::as_error_response(&err) {
+                    Some(rpc_err) if rpc_err.code == METHOD_NOT_FOUND => {
+                        println!(
+                            "[{}] eth_getBlockReceipts not supported, using batched eth_getTransactionReceipt",
+                            chain.to_uppercase()
+                        );
+                        self.block_receipts_supported.store(false, Ordering::Relaxed);
+                    }
+                    Some(_) => {}
+                    None => return Err(err.into()),
+                },
+            }
+        }
+
+        let receipts = match receipts {
+            Some(list) => list,
+            None => {
+                let hashes: Vec<H256> = block.transactions.iter().map(|tx| tx.hash).collect();
+                let _permit = metrics::acquire(chain, &self.rpc_limiter).await?;
+                self.batch.transaction_receipts(&hashes).await?.into_iter().flatten().collect()
+            }
+        };
+
+        // the node may have switched to another block at this height between the two calls
+        let receipts: HashMap<H256, TransactionReceipt> = receipts
+            .into_iter()
+            .filter(|receipt| receipt.block_hash == block.hash)
+            .map(|receipt| (receipt.transaction_hash, receipt))
+            .collect();
+
+        if let Some(tx) = block.transactions.iter().find(|tx| !receipts.contains_key(&tx.hash)) {
+            anyhow::bail!(
+                "[{}] no receipt for tx {:#x} of block {} (reorg in progress?)",
+                chain.to_uppercase(),
+                tx.hash,
+                number
+            );
+        }
+
+        Ok(receipts)
+    }
+
+    /// Looks up balance, nonce and code of every address in JSON-RPC batches and saves them.
+    async fn save_wallets(&self, addrs: Vec<Address>) -> Result<()> {
+        let mut seen = HashSet::new();
+        let addrs: Vec<Address> = addrs
+            .into_iter()
+            .filter(|addr| *addr != Address::zero() && seen.insert(*addr))
+            .collect();
+
+        if addrs.is_empty() {
+            return Ok(());
+        }
+
+        let states = {
+            let _permit = metrics::acquire(&self.chain, &self.rpc_limiter).await?;
+            self.batch.account_states(&addrs).await?
+        };
+
+        for (addr, state) in addrs.iter().zip(states) {
+            let wallet_type = if state.is_contract {
+                "smart_contract".to_string()
+            } else {
+                "wallet".to_string()
+            };
+
+            save_wallet(
+                &self.writer,
+                &format!("{:#x}", addr),
+                state.balance.to_string(),
+                state.nonce.as_u64(),
+                wallet_type,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+// استخراج Transfer Logs
+fn extract_token_transfers(receipt: &TransactionReceipt) -> Vec<(u32, Address, Address, Address, U256)> {
+    let mut transfers = Vec::new();
+
+    let transfer_topic: H256 = ERC20_TRANSFER_TOPIC.parse().unwrap();
+
+    for log in &receipt.logs {
+        if log.topics.len() == 3 && log.topics[0] == transfer_topic {
+            let token_address = log.address;
+
+            let from = Address::from_slice(&log.topics[1].as_bytes()[12..]);
+            let to = Address::from_slice(&log.topics[2].as_bytes()[12..]);
+
+            let amount = U256::from_big_endian(&log.data.0);
+
+            transfers.push((
+                log.log_index.unwrap_or(U256::zero()).as_u32(),
+                token_address,
+                from,
+                to,
+                amount,
+            ));
+        }
+    }
+
+    transfers
+}
+
+// stake / deposit / withdraw of the sender. Stakes have their own category; withdraws and
+// deposits aren't one and end up as plain transfers (or other)
+fn defi_position_row(
+    loader: &LoaderEvm,
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+    category: TxCategory,
+    block_number: u64,
+) -> Option<DefiPositionRow> {
+    let (action, user, protocol, asset, amount, receipt_token) = match category {
+        TxCategory::Stake => {
+            let stake = extract_stake_details(tx, receipt)?;
+            let minted = stake.received_asset.zip(stake.received_amount);
+            (
+                "stake",
+                stake.user,
+                stake.staking_contract,
+                stake.sent_asset,
+                stake.sent_amount,
+                minted,
+            )
+        }
+        TxCategory::ERC20Transfer | TxCategory::EthTransfer | TxCategory::Other => {
+            if let Some(withdraw) = extract_withdraw_details(tx, receipt) {
+                let burned = withdraw.burned_asset.zip(withdraw.burned_amount);
+                (
+                    "withdraw",
+                    withdraw.user,
+                    withdraw.staking_contract,
+                    withdraw.received_asset,
+                    withdraw.received_amount,
+                    burned,
+                )
+            } else {
+                let deposit = extract_deposit_details(tx, receipt)?;
+                let minted = deposit.received_asset.zip(deposit.received_amount);
+                (
+                    "deposit",
+                    deposit.user,
+                    deposit.contract,
+                    deposit.deposited_asset,
+                    deposit.deposited_amount,
+                    minted,
+                )
+            }
+        }
+        _ => return None,
+    };
+
+    let (receipt_token, receipt_amount) = match receipt_token {
+        Some((token, amount)) => (loader.asset_name(&token), amount.to_string()),
+        None => (String::new(), "0".to_string()),
+    };
+
+    Some(DefiPositionRow {
+        tx_hash: format!("{:#x}", tx.hash),
+        block_number,
+        user_addr: format!("{:#x}", user),
+        action: action.into(),
+        protocol: format!("{:#x}", protocol),
+        asset: loader.asset_name(&asset),
+        amount: amount.to_string(),
+        receipt_token,
+        receipt_amount,
+    })
+}
+
+// the sides of a swap zipped into legs, in a stable order so re-indexing replaces the same rows
+fn swap_row(
+    loader: &LoaderEvm,
+    tx: &Transaction,
+    receipt: &TransactionReceipt,
+    block_number: u64,
+) -> Option<DexSwapRow> {
+    let swap = extract_swap_details(tx, receipt)?;
+
+    let side = |amounts: Vec<AssetAmount>| -> (Vec<String>, Vec<String>) {
+        let mut side: Vec<(String, String)> = amounts
+            .into_iter()
+            .map(|leg| (loader.asset_name(&leg.asset), leg.amount.to_string()))
+            .collect();
+        side.sort();
+        side.into_iter().unzip()
+    };
+    let (assets_in, amounts_in) = side(swap.sent);
+    let (assets_out, amounts_out) = side(swap.received);
+
+    Some(DexSwapRow {
+        tx_hash: format!("{:#x}", tx.hash),
+        block_number,
+        user_addr: format!("{:#x}", swap.user),
+        assets_in,
+        amounts_in,
+        assets_out,
+        amounts_out,
+        router: format!("{:#x}", tx.to.unwrap_or_default()),
+    })
+}
+
+async fn process_tx(
+    loader: Arc<LoaderEvm>,
+    evm_tx: EvmTx,
+    block_number: u64,
+) -> Result<EvmTxOutput> {
+    let EvmTx { tx, receipt } = evm_tx;
+
+    let hash = format!("{:#x}", tx.hash);
+    let from = tx.from;
+    let to = tx.to.unwrap_or_default();
+    let value = tx.value;
+    let category = classify_tx(&tx, &receipt, &loader.bridges);
+
+    save_tx(
+        &loader.writer,
+        TransactionRow {
+            hash: hash.clone(),
+            block_number,
+            from_addr: format!("{:#x}", from),
+            to_addr: format!("{:#x}", to),
+            value: value.to_string(),
+            sensivity: loader.calc_sensivity(value) as u8,
+            category: Some(category as u8),
+            // pre-Byzantium receipts carry a state root instead of a status
+            status: receipt.status.map(|status| status.as_u64() as u8),
+        },
+    )
+    .await?;
+
+    match category {
+        TxCategory::Swap => {
+            if let Some(row) = swap_row(&loader, &tx, &receipt, block_number) {
+                save_dex_swap(&loader.writer, row).await?;
+            }
+        }
+        TxCategory::LiquidityPool => {
+            if let Some(lp) = extract_liquidity_pool(&tx, &receipt) {
+                save_liquidity_event(
+                    &loader.writer,
+                    LiquidityEventRow {
+                        tx_hash: hash.clone(),
+                        block_number,
+                        user_addr: format!("{:#x}", from),
+                        action: match lp.action {
+                            LiquidityAction::Add => "add".into(),
+                            LiquidityAction::Remove => "remove".into(),
+                        },
+                        pool: format!("{:#x}", lp.pool),
+                        token0: format!("{:#x}", lp.token0),
+                        token1: format!("{:#x}", lp.token1),
+                        amount0: lp.amount0.to_string(),
+                        amount1: lp.amount1.to_string(),
+                        lp_amount: lp.lp_amount.to_string(),
+                    },
+                )
+                .await?;
+            }
+        }
+        TxCategory::Bridge => {
+            if let Some(bridge) = extract_bridge_transfer(&tx, &receipt, &loader.bridges) {
+                let user_addr = format!("{:#x}", bridge.user);
+                let bridge_contract = format!("{:#x}", bridge.bridge_contract);
+                let asset = loader.asset_name(&match bridge.asset {
+                    BridgeAsset::Eth => Asset::Eth,
+                    BridgeAsset::Erc20(token) => Asset::Erc20(token),
+                });
+
+                save_bridge_transfer(
+                    &loader.writer,
+                    BridgeTransferRow {
+                        tx_hash: hash.clone(),
+                        block_number,
+                        user_addr: user_addr.clone(),
+                        asset: asset.clone(),
+                        amount: bridge.amount.to_string(),
+                        bridge_contract: bridge_contract.clone(),
+                        bridge_name: loader
+                            .bridges
+                            .name(&bridge.bridge_contract)
+                            .unwrap_or_default()
+                            .to_string(),
+                        event_sig: bridge
+                            .event_sig
+                            .map(|sig| format!("{:#x}", sig))
+                            .unwrap_or_default(),
+                    },
+                )
+                .await?;
+
+                save_money_flow(
+                    &loader.writer,
+                    MoneyFlowRow {
+                        tx_hash: hash.clone(),
+                        from_addr: user_addr,
+                        to_addr: bridge_contract,
+                        amount: bridge.amount.to_string(),
+                        asset,
+                        cross_chain_exit: true,
+                    },
+                )
+                .await?;
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(row) = defi_position_row(&loader, &tx, &receipt, category, block_number) {
+        save_defi_position(&loader.writer, row).await?;
+    }
+
+    let mut tokens: Vec<Address> = vec![];
+
+    for (log_index, token, from_addr, to_addr, amount) in extract_token_transfers(&receipt) {
+        tokens.push(token);
+
+        save_token_transfer(
+            &loader.writer,
+            TokenTransferRow {
+                tx_hash: hash.clone(),
+                block_number,
+                log_index,
+                token_address: format!("{:#x}", token),
+                from_addr: format!("{:#x}", from_addr),
+                to_addr: format!("{:#x}", to_addr),
+                amount: amount.to_string(),
+            },
+        )
+        .await?;
+    }
+
+    // wallet info is looked up for the whole block in after_block
+    let mut wallets = vec![from];
+    if let Some(to) = tx.to {
+        wallets.push(to);
+    }
+
+    Ok(EvmTxOutput { tokens, wallets })
+}
+
+impl ChainIndexer for LoaderEvm {
+    // بلاک کامل با tx ها و receipt ها، بدون call جدا برای هر tx
+    type Block = EvmBlock;
+    type Tx = EvmTx;
+    type TxOutput = EvmTxOutput;
+
+    fn chain(&self) -> &str {
+        &self.chain
+    }
+
+    fn clickhouse(&self) -> &Arc<Client> {
+        &self.clickhouse
+    }
+
+    fn writer(&self) -> &Arc<BatchWriter> {
+        &self.writer
+    }
+
+    fn sync_status(&self) -> &Arc<SyncStatus> {
+        &self.sync_status
+    }
+
+    async fn init_db(&self, admin: &Client) -> Result<()> {
+        init_evm_db(admin, &self.database).await
+    }
+
+    async fn head(&self) -> Result<u64> {
+        Ok(metrics::rpc(&self.chain, "get_block_number", self.provider.get_block_number())
+            .await?
+            .as_u64())
+    }
+
+    async fn confirmed_head(&self, head: u64) -> Result<u64> {
+        evm_confirmed_head(&self.chain, &self.provider, head, &self.finality).await
+    }
+
+    async fn fetch_block(&self, number: u64) -> Result<Option<Self::Block>> {
+        let block = {
+            let _permit = metrics::acquire(&self.chain, &self.rpc_limiter).await?;
+            metrics::rpc(
+                &self.chain,
+                "get_block_with_txs",
+                self.provider.get_block_with_txs(number),
+            )
+            .await?
+        };
+
+        let Some(block) = block else {
+            return Ok(None);
+        };
+
+        let receipts = self.block_receipts(number, &block).await?;
+        Ok(Some(EvmBlock { block, receipts }))
+    }
+
+    fn header(&self, block: &Self::Block) -> BlockHeader {
+        let block = &block.block;
+        BlockHeader {
+            hash: format!("{:#x}", block.hash.unwrap_or_default()),
+            parent_hash: format!("{:#x}", block.parent_hash),
+            time: DateTime::from_timestamp(block.timestamp.low_u64() as i64, 0),
+        }
+    }
+
+    fn block_txs(&self, block: Self::Block) -> Vec<EvmTx> {
+        let EvmBlock { block, mut receipts } = block;
+
+        // fetch_block made sure every tx has its receipt
+        block
+            .transactions
+            .into_iter()
+            .filter_map(|tx| {
+                let receipt = receipts.remove(&tx.hash)?;
+                Some(EvmTx { tx, receipt })
+            })
+            .collect()
+    }
+
+    fn process_tx(
+        self: Arc<Self>,
+        tx: EvmTx,
+        block_number: u64,
+    ) -> impl Future<Output = Result<EvmTxOutput>> + Send + 'static {
+        process_tx(self, tx, block_number)
+    }
+
+    async fn after_block(&self, outputs: Vec<EvmTxOutput>) -> Result<()> {
+        let mut discovered_tokens: Vec<Address> = vec![];
+        let mut wallets: Vec<Address> = vec![];
+        for output in outputs {
+            discovered_tokens.extend(output.tokens);
+            wallets.extend(output.wallets);
+        }
+
+        // Save wallet info (batched)
+        self.save_wallets(wallets).await?;
+
+        // Token metadata worker
+        if !discovered_tokens.is_empty() {
+            token_metadata_worker::process_new_tokens(
+                &self.chain,
+                &self.writer,
+                self.provider.clone(),
+                self.rpc_limiter.clone(),
+                discovered_tokens,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    fn reorg_max_depth(&self) -> Option<u64> {
+        Some(self.reorg_max_depth)
+    }
+}
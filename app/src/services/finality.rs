@@ -0,0 +1,32 @@
+use anyhow::Result;
+use ethers::providers::Middleware;
+use ethers::types::BlockNumber;
+
+use crate::config::Finality;
+use crate::helper::evm_rpc::EvmProvider;
+use crate::services::metrics;
+
+/// Highest EVM block that may be indexed: the node's `finalized` block when the tag is
+/// enabled and supported, otherwise `confirmations` below `head`.
+pub async fn evm_confirmed_head(
+    chain: &str,
+    provider: &EvmProvider,
+    head: u64,
+    finality: &Finality,
+) -> Result<u64> {
+    if finality.finalized_tag {
+        let finalized = metrics::rpc(
+            chain,
+            "get_block_finalized",
+            provider.get_block(BlockNumber::Finalized),
+        )
+        .await;
+
+        // nodes without the tag answer with an error or null; fall back to confirmations
+        if let Ok(Some(number)) = finalized.map(|b| b.and_then(|b| b.number)) {
+            return Ok(number.as_u64().min(head));
+        }
+    }
+
+    Ok(finality.confirmed_head(head))
+}
@@ -0,0 +1,73 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clickhouse::Client;
+
+use crate::services::batch_writer::BatchWriter;
+use crate::services::sync_status::SyncStatus;
+
+/// What the driver needs to know about a fetched block besides its transactions.
+pub struct BlockHeader {
+    pub hash: String,
+    // empty when the source doesn't expose it (only needed for reorg detection)
+    pub parent_hash: String,
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// One chain as seen by the generic fetch driver (`tasks::fetch_loop::run_indexer`).
+///
+/// The driver owns the block loop: start-block resolution, head polling and follow mode,
+/// the tx cap, sync_state / sync status bookkeeping, reorg checks, per-tx concurrency and
+/// shutdown. An implementation only knows how to talk to its node and what to persist.
+pub trait ChainIndexer: Send + Sync + 'static {
+    /// A fetched block, before it is split into transactions.
+    type Block: Send;
+    /// One unit of work handed to `process_tx`, run concurrently with the rest of the block.
+    type Tx: Send + 'static;
+    /// Whatever `process_tx` hands back for `after_block` (e.g. discovered token contracts).
+    type TxOutput: Send + 'static;
+
+    fn chain(&self) -> &str;
+    fn clickhouse(&self) -> &Arc<Client>;
+    /// Buffers the rows written while indexing; flushed by the driver at block boundaries.
+    fn writer(&self) -> &Arc<BatchWriter>;
+    fn sync_status(&self) -> &Arc<SyncStatus>;
+
+    /// Creates the chain's database and tables; `admin` is not bound to a database.
+    fn init_db(&self, admin: &Client) -> impl Future<Output = Result<()>> + Send;
+
+    /// Latest block the node knows about.
+    fn head(&self) -> impl Future<Output = Result<u64>> + Send;
+
+    /// Highest block at or below `head` that may be indexed.
+    fn confirmed_head(&self, head: u64) -> impl Future<Output = Result<u64>> + Send;
+
+    /// Fetches a block; None when the node doesn't serve it (yet).
+    fn fetch_block(&self, number: u64) -> impl Future<Output = Result<Option<Self::Block>>> + Send;
+
+    fn header(&self, block: &Self::Block) -> BlockHeader;
+
+    fn block_txs(&self, block: Self::Block) -> Vec<Self::Tx>;
+
+    /// Decodes one transaction and persists everything derived from it.
+    fn process_tx(
+        self: Arc<Self>,
+        tx: Self::Tx,
+        block_number: u64,
+    ) -> impl Future<Output = Result<Self::TxOutput>> + Send + 'static;
+
+    /// Runs once per block after all of its transactions were processed.
+    fn after_block(
+        &self,
+        _outputs: Vec<Self::TxOutput>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// How far back a reorg may be rolled back; None disables block hash tracking.
+    fn reorg_max_depth(&self) -> Option<u64> {
+        None
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::LazyLock;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::models::{TokenTransferRow, TransactionRow};
+
+// slow subscribers that fall this far behind skip ahead instead of holding the fetch loop back
+const FEED_CAPACITY: usize = 4096;
+
+static FEED: LazyLock<broadcast::Sender<FeedEvent>> =
+    LazyLock::new(|| broadcast::channel(FEED_CAPACITY).0);
+
+/// A row that has just been persisted by one of the fetch loops.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FeedEvent {
+    Transaction {
+        chain: String,
+        #[serde(flatten)]
+        row: TransactionRow,
+    },
+    TokenTransfer {
+        chain: String,
+        #[serde(flatten)]
+        row: TokenTransferRow,
+    },
+    // rows from `from_block` onwards were orphaned and removed; they are re-sent as re-indexed
+    Reorg {
+        chain: String,
+        from_block: u64,
+    },
+}
+
+impl FeedEvent {
+    pub fn chain(&self) -> &str {
+        match self {
+            FeedEvent::Transaction { chain, .. }
+            | FeedEvent::TokenTransfer { chain, .. }
+            | FeedEvent::Reorg { chain, .. } => chain,
+        }
+    }
+}
+
+/// Pushes an event to every live subscriber; a no-op when nobody is listening.
+pub fn publish(event: FeedEvent) {
+    let _ = FEED.send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<FeedEvent> {
+    FEED.subscribe()
+}
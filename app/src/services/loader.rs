@@ -1,136 +1,200 @@
 use clickhouse::Client;
 use ethers::prelude::*;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
 use tokio::sync::Semaphore;
 
+use crate::config::{AppConfig, ChainConfig, ChainKind, EvmParams, Finality, RpcEndpoint, redact_url};
+use crate::helper::evm_batch::EvmBatchClient;
+use crate::helper::evm_rpc::{EvmProvider, PooledHttp};
+use crate::helper::rpc_pool::{RetryPolicy, RpcPool};
 use crate::helper::tron::TronClient;
-
-pub struct LoaderEth {
-    pub clickhouse: Arc<Client>,
-    pub eth_provider: Arc<Provider<Http>>,
-    pub rpc_limiter: Arc<Semaphore>,
+use crate::services::batch_writer::{BatchWriter, WriterOptions};
+use crate::services::ethereum_detail::BridgeRegistry;
+use crate::services::metrics;
+use crate::services::sync_status::SyncStatus;
+
+fn chain_client(config: &AppConfig, chain: &ChainConfig) -> Arc<Client> {
+    Arc::new(
+        Client::default()
+            //.with_url("tcp://clickhouse:9000")
+            .with_url(&config.clickhouse_url)
+            .with_user(&config.clickhouse_user)
+            .with_password(&config.clickhouse_pass)
+            .with_database(&chain.database),
+    )
 }
 
-impl LoaderEth{
-    pub async fn new(config: &crate::config::AppConfig) -> anyhow::Result<Self> {
-        let clickhouse = Arc::new(
-            Client::default()
-                //.with_url("tcp://clickhouse:9000")
-                .with_url(&config.clickhouse_url)
-                .with_user(&config.clickhouse_user)
-                .with_password(&config.clickhouse_pass)
-                .with_database(&config.clickhouse_db_eth)
-        );
-
-        let eth_rpc_url = config
-            .eth_rpc_url
-            .as_ref()
-            .expect("ETH_RPC_HTTP must be set for eth mode");
-
-        let rpc_limiter = Arc::new(Semaphore::new(config.rpc_max_concurrency));
-
-        let eth_provider = Arc::new(
-            Provider::<Http>::try_from(eth_rpc_url.as_str())?
-        );
+fn chain_writer(config: &AppConfig, chain: &ChainConfig, clickhouse: Arc<Client>) -> Arc<BatchWriter> {
+    Arc::new(BatchWriter::new(
+        &chain.name,
+        clickhouse,
+        WriterOptions {
+            max_rows: config.writer_max_rows,
+            flush_interval: Duration::from_secs(config.writer_flush_interval_seconds),
+        },
+    ))
+}
 
-        Ok(Self {
-            clickhouse,
-            eth_provider,
-            rpc_limiter,
-        })
-    }
+fn chain_pool(
+    config: &AppConfig,
+    chain: &ChainConfig,
+    endpoints: &[RpcEndpoint],
+) -> anyhow::Result<Arc<RpcPool>> {
+    let policy = RetryPolicy {
+        max_retries: config.rpc_max_retries,
+        backoff: Duration::from_millis(config.rpc_backoff_ms),
+        max_backoff: Duration::from_millis(config.rpc_max_backoff_ms),
+        rate_limit_cooldown: Duration::from_secs(config.rpc_rate_limit_cooldown_seconds),
+    };
+
+    Ok(Arc::new(RpcPool::new(
+        &chain.name,
+        endpoints,
+        Duration::from_secs(config.rpc_timeout_seconds),
+        policy,
+    )?))
 }
 
-pub struct LoaderBtc {
+/// Shared by every EVM chain; `EvmParams` carries what differs between them.
+pub struct LoaderEvm {
+    pub chain: String,
+    pub database: String,
     pub clickhouse: Arc<Client>,
+    pub writer: Arc<BatchWriter>,
+    pub provider: Arc<EvmProvider>,
+    // receipts and account lookups in JSON-RPC batches
+    pub batch: EvmBatchClient,
+    // cleared once the node turns out not to serve eth_getBlockReceipts
+    pub block_receipts_supported: AtomicBool,
+    pub rpc_limiter: Arc<Semaphore>,
+    pub sync_status: Arc<SyncStatus>,
+    pub reorg_max_depth: u64,
+    pub finality: Finality,
+    pub params: EvmParams,
+    // params.bridges by address
+    pub bridges: BridgeRegistry,
 }
 
-impl LoaderBtc {
-    pub async fn new(config: &crate::config::AppConfig) -> anyhow::Result<Self> {
-        let clickhouse = Arc::new(
-                Client::default()
-                    .with_url(&config.clickhouse_url)
-                    .with_user(&config.clickhouse_user)
-                    .with_password(&config.clickhouse_pass)
-                    .with_database(&config.clickhouse_db_btc),
-            );
+impl LoaderEvm {
+    pub async fn new(
+        config: &AppConfig,
+        chain: &ChainConfig,
+        sync_status: Arc<SyncStatus>,
+    ) -> anyhow::Result<Self> {
+        let ChainKind::Evm(params) = &chain.kind else {
+            anyhow::bail!("{} is not an EVM chain", chain.name);
+        };
+
+        // a wrong RPC URL would otherwise fill this chain's database with another chain's blocks;
+        // every endpoint is checked on its own since the pool may send any call to any of them
+        if let Some(expected) = params.chain_id {
+            for endpoint in &chain.endpoints {
+                let single = chain_pool(config, chain, std::slice::from_ref(endpoint))?;
+                let provider = Provider::new(PooledHttp::new(single));
+                let actual = metrics::rpc(&chain.name, "get_chainid", provider.get_chainid())
+                    .await?
+                    .as_u64();
+                if actual != expected {
+                    anyhow::bail!(
+                        "[{}] RPC endpoint {} serves chain id {}, expected {}",
+                        chain.name.to_uppercase(),
+                        redact_url(&endpoint.url),
+                        actual,
+                        expected
+                    );
+                }
+            }
+        }
+
+        let pool = chain_pool(config, chain, &chain.endpoints)?;
+        let provider = Arc::new(Provider::new(PooledHttp::new(pool.clone())));
+        let batch = EvmBatchClient::new(&chain.name, pool, config.rpc_batch_size);
+
+        let clickhouse = chain_client(config, chain);
 
         Ok(Self {
-            clickhouse
+            chain: chain.name.clone(),
+            database: chain.database.clone(),
+            writer: chain_writer(config, chain, clickhouse.clone()),
+            clickhouse,
+            provider,
+            batch,
+            block_receipts_supported: AtomicBool::new(true),
+            rpc_limiter: Arc::new(Semaphore::new(chain.rpc_max_concurrency)),
+            sync_status,
+            reorg_max_depth: config.reorg_max_depth,
+            finality: chain.finality.clone(),
+            params: params.clone(),
+            bridges: BridgeRegistry::new(&params.bridges),
         })
     }
 }
 
-
-pub struct LoaderBsc {
+pub struct LoaderBtc {
+    pub database: String,
     pub clickhouse: Arc<Client>,
-    pub bsc_provider: Arc<Provider<Http>>,
-    pub rpc_limiter: Arc<Semaphore>,
+    pub writer: Arc<BatchWriter>,
+    pub pool: Arc<RpcPool>,
+    pub sync_status: Arc<SyncStatus>,
+    pub finality: Finality,
 }
 
-impl LoaderBsc {
-    pub async fn new(config: &crate::config::AppConfig) -> anyhow::Result<Self> {
-        let clickhouse = Arc::new(
-            Client::default()
-                .with_url(&config.clickhouse_url)
-                .with_user(&config.clickhouse_user)
-                .with_password(&config.clickhouse_pass)
-                .with_database(&config.clickhouse_db_bsc),
-        );
-
-        let bsc_rpc_url = config
-            .bsc_rpc_url
-            .as_ref()
-            .expect("BSC_RPC_HTTP must be set for bsc mode");
-
-        let bsc_provider = Arc::new(
-            Provider::<Http>::try_from(bsc_rpc_url.as_str())?
-        );
+impl LoaderBtc {
+    pub async fn new(
+        config: &AppConfig,
+        chain: &ChainConfig,
+        sync_status: Arc<SyncStatus>,
+    ) -> anyhow::Result<Self> {
+        let pool = chain_pool(config, chain, &chain.endpoints)?;
 
-        let rpc_limiter = Arc::new(Semaphore::new(config.rpc_max_concurrency));
+        let clickhouse = chain_client(config, chain);
 
         Ok(Self {
+            database: chain.database.clone(),
+            writer: chain_writer(config, chain, clickhouse.clone()),
             clickhouse,
-            bsc_provider,
-            rpc_limiter,
+            pool,
+            sync_status,
+            finality: chain.finality.clone(),
         })
     }
 }
 
 
 pub struct LoaderTron {
+    pub database: String,
     pub clickhouse: Arc<Client>,
+    pub writer: Arc<BatchWriter>,
     pub tron_client: Arc<TronClient>,
     pub rpc_limiter: Arc<Semaphore>,
+    pub sync_status: Arc<SyncStatus>,
+    pub finality: Finality,
 }
 
 impl LoaderTron {
-    pub async fn new(config: &crate::config::AppConfig) -> anyhow::Result<Self> {
-        let clickhouse = Arc::new(
-            Client::default()
-                .with_url(&config.clickhouse_url)
-                .with_user(&config.clickhouse_user)
-                .with_password(&config.clickhouse_pass)
-                .with_database(&config.clickhouse_db_tron),
-        );
-
-        let tron_rpc_url = config
-            .tron_rpc_url
-            .as_ref()
-            .expect("TRON_RPC_HTTP must be set for tron mode");
-
-        let tron_client = Arc::new(
-            TronClient::new(tron_rpc_url.clone(), config.tron_api_key.clone())
-        );
+    pub async fn new(
+        config: &AppConfig,
+        chain: &ChainConfig,
+        sync_status: Arc<SyncStatus>,
+    ) -> anyhow::Result<Self> {
+        let pool = chain_pool(config, chain, &chain.endpoints)?;
+        let tron_client = Arc::new(TronClient::new(pool, chain.api_key.clone())?);
 
         let rpc_limiter =
-            Arc::new(Semaphore::new(config.rpc_max_concurrency));
+            Arc::new(Semaphore::new(chain.rpc_max_concurrency));
+
+        let clickhouse = chain_client(config, chain);
 
         Ok(Self {
+            database: chain.database.clone(),
+            writer: chain_writer(config, chain, clickhouse.clone()),
             clickhouse,
             tron_client,
             rpc_limiter,
+            sync_status,
+            finality: chain.finality.clone(),
         })
     }
 }
-
@@ -0,0 +1,287 @@
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use prometheus::{
+    Encoder,
+    GaugeVec,
+    HistogramOpts,
+    HistogramVec,
+    IntCounterVec,
+    IntGaugeVec,
+    Opts,
+    Registry,
+    TextEncoder,
+};
+use tokio::sync::{AcquireError, Semaphore, SemaphorePermit};
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+// latency buckets from 5ms up to 2 minutes (rpc_timeout_seconds defaults to 120)
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0,
+];
+
+static RPC_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_rpc_requests_total", "RPC calls per chain and method"),
+        &["chain", "method"],
+    ))
+});
+
+static RPC_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_rpc_errors_total", "Failed RPC calls per chain and method"),
+        &["chain", "method"],
+    ))
+});
+
+static RPC_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("fetcher_rpc_latency_seconds", "RPC call latency")
+            .buckets(LATENCY_BUCKETS.to_vec()),
+        &["chain", "method"],
+    ))
+});
+
+static ENDPOINT_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_rpc_endpoint_requests_total", "Requests per pooled RPC endpoint and outcome"),
+        &["chain", "endpoint", "outcome"],
+    ))
+});
+
+static ENDPOINT_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("fetcher_rpc_endpoint_latency_seconds", "Request latency per pooled RPC endpoint")
+            .buckets(LATENCY_BUCKETS.to_vec()),
+        &["chain", "endpoint"],
+    ))
+});
+
+static ENDPOINT_HEALTH: LazyLock<GaugeVec> = LazyLock::new(|| {
+    register(GaugeVec::new(
+        Opts::new("fetcher_rpc_endpoint_health", "Health score of a pooled RPC endpoint, 0 (failing) to 1 (healthy)"),
+        &["chain", "endpoint"],
+    ))
+});
+
+static RPC_RETRIES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_rpc_retries_total", "RPC requests retried on another endpoint"),
+        &["chain", "method"],
+    ))
+});
+
+static INSERT_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("fetcher_clickhouse_insert_seconds", "ClickHouse insert latency per table")
+            .buckets(LATENCY_BUCKETS.to_vec()),
+        &["table"],
+    ))
+});
+
+static INSERT_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_clickhouse_insert_errors_total", "Failed ClickHouse inserts per table"),
+        &["table"],
+    ))
+});
+
+static INSERT_ROWS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_clickhouse_rows_written_total", "Rows written per table"),
+        &["table"],
+    ))
+});
+
+static WRITER_PENDING: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new("fetcher_writer_pending_rows", "Rows buffered by the batch writer, not yet inserted"),
+        &["chain", "table"],
+    ))
+});
+
+static WRITER_WAIT: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("fetcher_writer_backpressure_seconds", "Time a fetcher waited for a full buffer to be inserted")
+            .buckets(LATENCY_BUCKETS.to_vec()),
+        &["chain", "table"],
+    ))
+});
+
+static LIMITER_WAIT: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register(HistogramVec::new(
+        HistogramOpts::new("fetcher_rpc_limiter_wait_seconds", "Time spent waiting for an rpc_limiter permit")
+            .buckets(LATENCY_BUCKETS.to_vec()),
+        &["chain"],
+    ))
+});
+
+static BLOCKS_PROCESSED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_blocks_processed_total", "Blocks fully processed"),
+        &["chain"],
+    ))
+});
+
+static TXS_PROCESSED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_txs_processed_total", "Transactions processed"),
+        &["chain"],
+    ))
+});
+
+static LOOP_RESTARTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_loop_restarts_total", "Chain fetch loops restarted after a failure"),
+        &["chain"],
+    ))
+});
+
+static REORGS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_reorgs_total", "Chain reorganizations rolled back"),
+        &["chain"],
+    ))
+});
+
+static REORG_BLOCKS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("fetcher_reorg_blocks_total", "Orphaned blocks removed by reorg rollbacks"),
+        &["chain"],
+    ))
+});
+
+static HEAD_LAG: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new("fetcher_head_lag_blocks", "Blocks between the confirmed head and the last processed block"),
+        &["chain"],
+    ))
+});
+
+fn register<M: prometheus::core::Collector + Clone + 'static>(
+    metric: prometheus::Result<M>,
+) -> M {
+    let metric = metric.expect("invalid metric definition");
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("metric registered twice");
+    metric
+}
+
+/// Runs one RPC call and records its count, latency and outcome.
+pub async fn rpc<T, E, F>(chain: &str, method: &str, call: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = call.await;
+
+    RPC_REQUESTS.with_label_values(&[chain, method]).inc();
+    RPC_LATENCY
+        .with_label_values(&[chain, method])
+        .observe(started.elapsed().as_secs_f64());
+    if result.is_err() {
+        RPC_ERRORS.with_label_values(&[chain, method]).inc();
+    }
+
+    result
+}
+
+/// One request to a pooled endpoint; `outcome` is `ok` or the kind of failure.
+pub fn record_endpoint_request(chain: &str, endpoint: &str, outcome: &str, elapsed: std::time::Duration) {
+    ENDPOINT_REQUESTS
+        .with_label_values(&[chain, endpoint, outcome])
+        .inc();
+    ENDPOINT_LATENCY
+        .with_label_values(&[chain, endpoint])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn set_endpoint_health(chain: &str, endpoint: &str, score: f64) {
+    ENDPOINT_HEALTH.with_label_values(&[chain, endpoint]).set(score);
+}
+
+pub fn record_rpc_retry(chain: &str, method: &str) {
+    RPC_RETRIES.with_label_values(&[chain, method]).inc();
+}
+
+/// Runs one ClickHouse insert and records its latency and outcome.
+pub async fn insert<T, E, F>(table: &str, write: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = write.await;
+
+    INSERT_LATENCY
+        .with_label_values(&[table])
+        .observe(started.elapsed().as_secs_f64());
+    if result.is_err() {
+        INSERT_ERRORS.with_label_values(&[table]).inc();
+    }
+
+    result
+}
+
+pub fn record_rows_written(table: &str, rows: usize) {
+    INSERT_ROWS.with_label_values(&[table]).inc_by(rows as u64);
+}
+
+pub fn set_pending_rows(chain: &str, table: &str, rows: usize) {
+    WRITER_PENDING
+        .with_label_values(&[chain, table])
+        .set(rows as i64);
+}
+
+pub fn observe_writer_wait(chain: &str, table: &str, waited: std::time::Duration) {
+    WRITER_WAIT
+        .with_label_values(&[chain, table])
+        .observe(waited.as_secs_f64());
+}
+
+/// `limiter.acquire()` that records how long the caller waited.
+pub async fn acquire<'a>(
+    chain: &str,
+    limiter: &'a Semaphore,
+) -> Result<SemaphorePermit<'a>, AcquireError> {
+    let started = Instant::now();
+    let permit = limiter.acquire().await;
+
+    LIMITER_WAIT
+        .with_label_values(&[chain])
+        .observe(started.elapsed().as_secs_f64());
+
+    permit
+}
+
+pub fn record_block(chain: &str, tx_count: u64) {
+    BLOCKS_PROCESSED.with_label_values(&[chain]).inc();
+    TXS_PROCESSED.with_label_values(&[chain]).inc_by(tx_count);
+}
+
+pub fn record_restart(chain: &str) {
+    LOOP_RESTARTS.with_label_values(&[chain]).inc();
+}
+
+pub fn record_reorg(chain: &str, depth: u64) {
+    REORGS.with_label_values(&[chain]).inc();
+    REORG_BLOCKS.with_label_values(&[chain]).inc_by(depth);
+}
+
+pub fn set_head_lag(chain: &str, lag_blocks: u64) {
+    HEAD_LAG
+        .with_label_values(&[chain])
+        .set(lag_blocks.min(i64::MAX as u64) as i64);
+}
+
+/// Prometheus text exposition of every registered metric.
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .expect("text encoding of metrics failed");
+
+    String::from_utf8(buffer).unwrap_or_default()
+}
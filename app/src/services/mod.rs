@@ -1,11 +1,18 @@
 pub mod bitcoin;
-pub mod ethereum;
-pub mod bsc;
+pub mod evm;
 pub mod tron;
 
 // helper section
+pub mod batch_writer;
+pub mod live_feed;
 pub mod loader;
+pub mod metrics;
 pub mod progress;
+pub mod reorg;
+pub mod shutdown;
 pub mod ethereum_detail;
+pub mod finality;
+pub mod indexer;
 pub mod token_metadata_worker;
-pub mod sync_logic;
\ No newline at end of file
+pub mod sync_logic;
+pub mod sync_status;
\ No newline at end of file
@@ -5,6 +5,15 @@ use crate::models::transaction::TransactionRow;
 use crate::models::token_metadata::TokenMetadataRow;
 use crate::models::contract_call::ContractCallRow;
 use crate::models::money_flow::MoneyFlowRow;
+use crate::models::block::BlockRow;
+use crate::models::backfill_range::BackfillRangeRow;
+use crate::models::dex_swap::DexSwapRow;
+use crate::models::liquidity_event::LiquidityEventRow;
+use crate::models::bridge_transfer::BridgeTransferRow;
+use crate::models::defi_position::DefiPositionRow;
+
+use crate::services::batch_writer::BatchWriter;
+use crate::services::metrics;
 
 use clickhouse::Client;
 use std::sync::Arc;
@@ -13,36 +22,19 @@ use nanoid::nanoid;
 
 // TRANSACTION
 
+// rows are buffered per table and published on the live feed once inserted
+
 pub async fn save_tx(
-    clickhouse: Arc<Client>,
-    hash: String,
-    block_number: u64,
-    from: String,
-    to: String,
-    value: String,
-    sensivity: u8,
+    writer: &BatchWriter,
+    tx_row: TransactionRow,
 ) -> Result<()> {
-
-    let tx_row = TransactionRow {
-        hash,
-        block_number,
-        from_addr: from,
-        to_addr: to,
-        value,
-        sensivity,
-    };
-
-    let mut insert = clickhouse.insert::<TransactionRow>("transactions").await?;
-    insert.write(&tx_row).await?;
-    insert.end().await?;
-
-    Ok(())
+    writer.write(&writer.transactions, tx_row).await
 }
 
 // WALLET + OWNER (AUTO TAGGING)
 
 pub async fn save_wallet(
-    clickhouse: Arc<Client>,
+    writer: &BatchWriter,
     addr: &str,
     balance: String,
     nonce: u64,
@@ -53,13 +45,15 @@ pub async fn save_wallet(
         return Ok(());
     }
 
+    let clickhouse = writer.clickhouse();
+
     // تشخیص نوع آدرس (wallet / exchange / contract)
-    let wallet_type = detect_wallet_type(&clickhouse, addr, nonce, mwallet_type).await?;
+    let wallet_type = detect_wallet_type(clickhouse, addr, nonce, mwallet_type).await?;
 
     // تعیین person_id
     let person_id = match wallet_type.as_str() {
         "exchange" => format!("EXCHANGE_{}", addr), // entity مشترک
-        _ => get_or_create_person_id(&clickhouse, addr).await?,
+        _ => get_or_create_person_id(writer, addr).await?,
     };
 
     let wallet = WalletRow {
@@ -78,14 +72,10 @@ pub async fn save_wallet(
     };
 
     // wallet_info
-    let mut insert_wallet = clickhouse.insert::<WalletRow>("wallet_info").await?;
-    insert_wallet.write(&wallet).await?;
-    insert_wallet.end().await?;
+    writer.write(&writer.wallets, wallet).await?;
 
     // owner_info
-    let mut insert_owner = clickhouse.insert::<OwnerRow>("owner_info").await?;
-    insert_owner.write(&owner).await?;
-    insert_owner.end().await?;
+    writer.write(&writer.owners, owner).await?;
 
     Ok(())
 }
@@ -142,10 +132,20 @@ async fn detect_wallet_type(
 // PERSON ID MANAGEMENT
 
 async fn get_or_create_person_id(
-    clickhouse: &Client,
+    writer: &BatchWriter,
     address: &str,
 ) -> Result<String> {
 
+    // an address seen earlier in the same batch isn't in ClickHouse yet
+    if let Some(person_id) = writer
+        .wallets
+        .find_map(|row| (row.address == address).then(|| row.person_id.clone()))
+    {
+        return Ok(person_id);
+    }
+
+    let clickhouse = writer.clickhouse();
+
     let existing = clickhouse
         .query(
             "SELECT person_id
@@ -175,36 +175,59 @@ pub fn generate_person_id() -> String {
 // Start of Ethereum section
 
 pub async fn save_token_transfer(
-    clickhouse: Arc<Client>,
+    writer: &BatchWriter,
     row: TokenTransferRow,
 ) -> Result<()> {
-
-    let mut insert =
-        clickhouse.insert::<TokenTransferRow>("token_transfers").await?;
-
-    insert.write(&row).await?;
-    insert.end().await?;
-
-    Ok(())
+    writer.write(&writer.token_transfers, row).await
 }
 
 pub async fn save_token_metadata(
-    clickhouse: Arc<Client>,
+    writer: &BatchWriter,
     row: TokenMetadataRow,
 ) -> Result<()> {
+    writer.write(&writer.token_metadata, row).await
+}
 
-    let mut insert = clickhouse
-        .insert::<TokenMetadataRow>("token_metadata")
-        .await?;
+pub async fn save_dex_swap(
+    writer: &BatchWriter,
+    row: DexSwapRow,
+) -> Result<()> {
+    writer.write(&writer.dex_swaps, row).await
+}
+
+pub async fn save_liquidity_event(
+    writer: &BatchWriter,
+    row: LiquidityEventRow,
+) -> Result<()> {
+    writer.write(&writer.liquidity_events, row).await
+}
 
-    insert.write(&row).await?;
-    insert.end().await?;
+pub async fn save_bridge_transfer(
+    writer: &BatchWriter,
+    row: BridgeTransferRow,
+) -> Result<()> {
+    writer.write(&writer.bridge_transfers, row).await
+}
 
-    Ok(())
+pub async fn save_defi_position(
+    writer: &BatchWriter,
+    row: DefiPositionRow,
+) -> Result<()> {
+    writer.write(&writer.defi_positions, row).await
 }
 // End of Ethereum section
 
+// BLOCKS (EVM reorg detection)
+
+pub async fn save_block(
+    writer: &BatchWriter,
+    row: BlockRow,
+) -> Result<()> {
+    writer.write(&writer.blocks, row).await
+}
+
 // SYNC STATE
+// written directly: it is the commit marker, so every buffered row has to be flushed first
 
 #[derive(Debug, clickhouse::Row, serde::Serialize)]
 pub struct SyncStateRow {
@@ -223,38 +246,44 @@ pub async fn save_sync_state(
         last_synced_block,
     };
 
-    let mut insert = clickhouse.insert::<SyncStateRow>("sync_state").await?;
-    insert.write(&row).await?;
-    insert.end().await?;
+    metrics::insert("sync_state", async {
+        let mut insert = clickhouse.insert::<SyncStateRow>("sync_state").await?;
+        insert.write(&row).await?;
+        insert.end().await
+    })
+    .await?;
+
+    Ok(())
+}
+
+// BACKFILL CHECKPOINT
+// like sync_state: written directly, after the shard's block was flushed
+
+pub async fn save_backfill_range(
+    clickhouse: &Client,
+    row: &BackfillRangeRow,
+) -> Result<()> {
+    metrics::insert("backfill_ranges", async {
+        let mut insert = clickhouse.insert::<BackfillRangeRow>("backfill_ranges").await?;
+        insert.write(row).await?;
+        insert.end().await
+    })
+    .await?;
 
     Ok(())
 }
 
 // Tron Section
 pub async fn save_contract_call(
-    clickhouse: Arc<Client>,
+    writer: &BatchWriter,
     row: ContractCallRow,
 ) -> Result<()> {
-    let mut insert = clickhouse
-        .insert::<ContractCallRow>("contract_calls")
-        .await?;
-
-    insert.write(&row).await?;
-    insert.end().await?;
-
-    Ok(())
+    writer.write(&writer.contract_calls, row).await
 }
 
 pub async fn save_money_flow(
-    clickhouse: Arc<Client>,
+    writer: &BatchWriter,
     row: MoneyFlowRow,
 ) -> Result<()> {
-    let mut insert = clickhouse
-        .insert::<MoneyFlowRow>("money_flows")
-        .await?;
-
-    insert.write(&row).await?;
-    insert.end().await?;
-
-    Ok(())
+    writer.write(&writer.money_flows, row).await
 }
\ No newline at end of file
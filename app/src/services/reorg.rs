@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+
+use crate::db::blocks::get_block_hash;
+use crate::db::reorg::rollback_from_block;
+use crate::services::indexer::ChainIndexer;
+use crate::services::live_feed::{self, FeedEvent};
+use crate::services::metrics;
+use crate::services::shutdown;
+
+// wait before refetching a block the pooled nodes disagree on
+const DISAGREEMENT_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Called when the block at `block_number` doesn't build on the stored previous block.
+/// Walks back to the last stored block that is still canonical, rolls back everything
+/// after it and returns the first block to re-index. When that is the block itself (the
+/// nodes disagree on it), nothing is rolled back and the block is retried after a pause.
+pub async fn rollback_to_fork_point<I: ChainIndexer>(
+    indexer: &I,
+    block_number: u64,
+    max_depth: u64,
+) -> Result<u64> {
+    let chain = indexer.chain();
+    let clickhouse = indexer.clickhouse();
+    let mut fork_block = None;
+
+    for depth in 1..=max_depth.min(block_number) {
+        let candidate = block_number - depth;
+
+        // nothing stored this far back (first run / pre-upgrade data): can't verify further
+        let Some(stored) = get_block_hash(clickhouse, candidate).await? else {
+            fork_block = Some(candidate + 1);
+            break;
+        };
+
+        let canonical_hash = indexer
+            .fetch_block(candidate)
+            .await?
+            .map(|block| indexer.header(&block).hash);
+
+        if canonical_hash.as_deref() == Some(stored.as_str()) {
+            fork_block = Some(candidate + 1);
+            break;
+        }
+    }
+
+    let Some(fork_block) = fork_block else {
+        bail!(
+            "[{}] reorg at block {} is deeper than {} blocks, refusing to roll back",
+            chain.to_uppercase(),
+            block_number,
+            max_depth
+        );
+    };
+
+    // the parent is still canonical: the block came from a node on another branch than
+    // the one that confirmed its parent. Nothing to roll back, wait for the nodes to agree
+    if fork_block >= block_number {
+        println!(
+            "[{}] block {} doesn't build on stored block {}, which is still canonical: \
+             nodes disagree, retrying in {:?}",
+            chain.to_uppercase(),
+            block_number,
+            block_number - 1,
+            DISAGREEMENT_BACKOFF
+        );
+        shutdown::sleep(DISAGREEMENT_BACKOFF).await;
+        return Ok(block_number);
+    }
+
+    let depth = block_number - fork_block;
+    println!(
+        "[{}] reorg detected at block {}: rolling back {} block(s) from {}",
+        chain.to_uppercase(),
+        block_number,
+        depth,
+        fork_block
+    );
+
+    rollback_from_block(clickhouse, chain, fork_block).await?;
+    metrics::record_reorg(chain, depth);
+    live_feed::publish(FeedEvent::Reorg {
+        chain: chain.to_string(),
+        from_block: fork_block,
+    });
+
+    Ok(fork_block)
+}
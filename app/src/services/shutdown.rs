@@ -0,0 +1,57 @@
+use std::sync::LazyLock;
+
+use tokio::sync::watch;
+
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+/// Asks every fetch loop to stop after its current block and the API to stop serving.
+pub fn trigger() {
+    SHUTDOWN.send_replace(true);
+}
+
+pub fn is_requested() -> bool {
+    *SHUTDOWN.borrow()
+}
+
+/// Resolves once shutdown has been requested.
+pub async fn requested() {
+    let mut receiver = SHUTDOWN.subscribe();
+    // the sender lives in a static, so the channel never closes
+    let _ = receiver.wait_for(|stop| *stop).await;
+}
+
+/// Sleeps for `duration`; returns false when shutdown was requested in the meantime.
+pub async fn sleep(duration: std::time::Duration) -> bool {
+    tokio::select! {
+        _ = tokio::time::sleep(duration) => !is_requested(),
+        _ = requested() => false,
+    }
+}
+
+/// Triggers shutdown on Ctrl-C / SIGTERM.
+pub async fn listen_for_signals() {
+    let ctrl_c = tokio::signal::ctrl_c();
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                eprintln!("[SHUTDOWN] cannot listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    println!("[SHUTDOWN] signal received, finishing in-flight blocks...");
+    trigger();
+}
@@ -0,0 +1,207 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Serialize;
+
+use crate::config::SyncMode;
+use crate::services::metrics;
+
+/// Asks the chain's RPC for its current head block.
+pub type HeadProbe = Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<u64>> + Send + Sync>;
+
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct ChainProgress {
+    sync_mode: Option<SyncMode>,
+    head_block: Option<u64>,
+    // highest block the loop may index under the chain's finality policy
+    confirmed_head: Option<u64>,
+    last_block: Option<u64>,
+    // on-chain timestamp of last_block
+    last_block_time: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+    last_error_at: Option<DateTime<Utc>>,
+    // (processed at, tx count) per block, trimmed to THROUGHPUT_WINDOW
+    recent_blocks: VecDeque<(Instant, u64)>,
+    probe: Option<HeadProbe>,
+    restarts: u32,
+    // set once the supervisor stops restarting the loop
+    stopped: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainSnapshot {
+    pub sync_mode: Option<String>,
+    pub head_block: Option<u64>,
+    pub confirmed_head: Option<u64>,
+    pub last_processed_block: Option<u64>,
+    pub lag_blocks: Option<u64>,
+    pub lag_seconds: Option<i64>,
+    pub txs_last_minute: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub restarts: u32,
+    pub stopped: bool,
+}
+
+/// In-memory progress of the fetch loops running in this process,
+/// shared between the loops (writers) and the HTTP API (readers).
+#[derive(Default)]
+pub struct SyncStatus {
+    chains: RwLock<HashMap<String, ChainProgress>>,
+}
+
+impl ChainProgress {
+    // lag is measured against the confirmed head, since newer blocks are not indexed by design
+    fn lag(&self, last: u64) -> Option<u64> {
+        self.confirmed_head
+            .or(self.head_block)
+            .map(|head| head.saturating_sub(last))
+    }
+}
+
+impl SyncStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, chain: &str, sync_mode: SyncMode, probe: HeadProbe) {
+        let mut chains = self.chains.write().unwrap();
+        let progress = chains.entry(chain.to_string()).or_default();
+        progress.sync_mode = Some(sync_mode);
+        progress.probe = Some(probe);
+    }
+
+    pub fn set_head(&self, chain: &str, head_block: u64) {
+        let mut chains = self.chains.write().unwrap();
+        let progress = chains.entry(chain.to_string()).or_default();
+        progress.head_block = Some(head_block);
+
+        if let Some(lag) = progress.last_block.and_then(|last| progress.lag(last)) {
+            metrics::set_head_lag(chain, lag);
+        }
+    }
+
+    pub fn set_confirmed_head(&self, chain: &str, confirmed_head: u64) {
+        let mut chains = self.chains.write().unwrap();
+        let progress = chains.entry(chain.to_string()).or_default();
+        progress.confirmed_head = Some(confirmed_head);
+
+        if let Some(lag) = progress.last_block.and_then(|last| progress.lag(last)) {
+            metrics::set_head_lag(chain, lag);
+        }
+    }
+
+    pub fn record_block(
+        &self,
+        chain: &str,
+        block_number: u64,
+        block_time: Option<DateTime<Utc>>,
+        tx_count: u64,
+    ) {
+        let now = Instant::now();
+        let mut chains = self.chains.write().unwrap();
+        let progress = chains.entry(chain.to_string()).or_default();
+
+        progress.last_block = Some(block_number);
+        if block_time.is_some() {
+            progress.last_block_time = block_time;
+        }
+
+        metrics::record_block(chain, tx_count);
+        if let Some(lag) = progress.lag(block_number) {
+            metrics::set_head_lag(chain, lag);
+        }
+
+        progress.recent_blocks.push_back((now, tx_count));
+        while let Some((at, _)) = progress.recent_blocks.front() {
+            if now.duration_since(*at) > THROUGHPUT_WINDOW {
+                progress.recent_blocks.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn record_error(&self, chain: &str, err: &anyhow::Error) {
+        let mut chains = self.chains.write().unwrap();
+        let progress = chains.entry(chain.to_string()).or_default();
+        progress.last_error = Some(format!("{:#}", err));
+        progress.last_error_at = Some(Utc::now());
+    }
+
+    pub fn record_restart(&self, chain: &str) {
+        let mut chains = self.chains.write().unwrap();
+        chains.entry(chain.to_string()).or_default().restarts += 1;
+        metrics::record_restart(chain);
+    }
+
+    pub fn mark_stopped(&self, chain: &str) {
+        let mut chains = self.chains.write().unwrap();
+        chains.entry(chain.to_string()).or_default().stopped = true;
+    }
+
+    /// Chains with a fetch loop registered in this process.
+    pub fn chains(&self) -> Vec<String> {
+        let chains = self.chains.read().unwrap();
+        let mut names: Vec<String> = chains.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn probe(&self, chain: &str) -> Option<HeadProbe> {
+        self.chains.read().unwrap().get(chain)?.probe.clone()
+    }
+
+    pub fn sync_mode(&self, chain: &str) -> Option<SyncMode> {
+        self.chains.read().unwrap().get(chain)?.sync_mode.clone()
+    }
+
+    /// `last_synced` (from sync_state) is used when the loop hasn't processed a block yet.
+    pub fn snapshot(&self, chain: &str, last_synced: Option<u64>) -> ChainSnapshot {
+        let chains = self.chains.read().unwrap();
+        let progress = chains.get(chain);
+
+        let head_block = progress.and_then(|p| p.head_block);
+        let confirmed_head = progress.and_then(|p| p.confirmed_head);
+        let last_processed_block = progress.and_then(|p| p.last_block).or(last_synced);
+
+        let lag_blocks = match (progress, last_processed_block) {
+            (Some(p), Some(last)) => p.lag(last),
+            _ => None,
+        };
+
+        let lag_seconds = progress
+            .and_then(|p| p.last_block_time)
+            .map(|t| (Utc::now() - t).num_seconds().max(0));
+
+        let now = Instant::now();
+        let txs_last_minute = progress
+            .map(|p| {
+                p.recent_blocks
+                    .iter()
+                    .filter(|(at, _)| now.duration_since(*at) <= THROUGHPUT_WINDOW)
+                    .map(|(_, txs)| txs)
+                    .sum()
+            })
+            .unwrap_or(0);
+
+        ChainSnapshot {
+            sync_mode: progress.and_then(|p| p.sync_mode.as_ref()).map(|m| format!("{:?}", m)),
+            head_block,
+            confirmed_head,
+            last_processed_block,
+            lag_blocks,
+            lag_seconds,
+            txs_last_minute,
+            last_error: progress.and_then(|p| p.last_error.clone()),
+            last_error_at: progress.and_then(|p| p.last_error_at),
+            restarts: progress.map(|p| p.restarts).unwrap_or(0),
+            stopped: progress.is_some_and(|p| p.stopped),
+        }
+    }
+}
@@ -2,13 +2,14 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use anyhow::Result;
-use clickhouse::Client;
 use ethers::contract::abigen;
 use ethers::providers::Middleware;
 use ethers::types::Address;
 use tokio::sync::Semaphore;
 
 use crate::models::token_metadata::TokenMetadataRow;
+use crate::services::batch_writer::BatchWriter;
+use crate::services::metrics;
 use crate::services::progress::save_token_metadata;
 
 // ERC20 ABI
@@ -23,7 +24,8 @@ abigen!(
 );
 
 pub async fn process_new_tokens<M: Middleware + 'static>(
-    clickhouse: Arc<Client>,
+    chain: &str,
+    writer: &BatchWriter,
     provider: Arc<M>,
     limiter: Arc<Semaphore>,
     discovered_tokens: Vec<Address>,
@@ -42,7 +44,8 @@ pub async fn process_new_tokens<M: Middleware + 'static>(
         let token_str = format!("{:?}", token_address);
 
         // check if token already exists
-        let exists: u64 = clickhouse
+        let exists: u64 = writer
+            .clickhouse()
             .query(
                 "SELECT count()
                  FROM token_metadata
@@ -57,18 +60,22 @@ pub async fn process_new_tokens<M: Middleware + 'static>(
             continue;
         }
 
-        let _permit = limiter.acquire().await?;
+        let _permit = metrics::acquire(chain, &limiter).await?;
 
         let contract = ERC20Contract::new(token_address, provider.clone());
 
         // fetch metadata (safe calls)
-        let name = contract.name().call().await.unwrap_or_else(|_| "UNKNOWN".into());
-        let symbol = contract.symbol().call().await.unwrap_or_else(|_| "UNKNOWN".into());
-        let decimals = contract.decimals().call().await.unwrap_or(0u8);
+        let name = metrics::rpc(chain, "erc20_name", contract.name().call())
+            .await
+            .unwrap_or_else(|_| "UNKNOWN".into());
+        let symbol = metrics::rpc(chain, "erc20_symbol", contract.symbol().call())
+            .await
+            .unwrap_or_else(|_| "UNKNOWN".into());
+        let decimals = metrics::rpc(chain, "erc20_decimals", contract.decimals().call())
+            .await
+            .unwrap_or(0u8);
 
-        let total_supply = contract
-            .total_supply()
-            .call()
+        let total_supply = metrics::rpc(chain, "erc20_total_supply", contract.total_supply().call())
             .await
             .map(|v| v.to_string())
             .unwrap_or_else(|_| "0".to_string());
@@ -82,7 +89,7 @@ pub async fn process_new_tokens<M: Middleware + 'static>(
             is_verified: 1, // فعلا همیشه 1 میذاریم (بعدا میشه verify logic اضافه کرد)
         };
 
-        save_token_metadata(clickhouse.clone(), row).await?;
+        save_token_metadata(writer, row).await?;
     }
 
     Ok(())
@@ -1,21 +1,28 @@
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
-use futures::stream::{FuturesUnordered, StreamExt};
+use chrono::DateTime;
+use clickhouse::Client;
 use serde_json::Value;
 
+use crate::db::init_tron::init_tron_db;
+
 use crate::models::contract_call::ContractCallRow;
 use crate::models::money_flow::MoneyFlowRow;
 use crate::models::token_transfer::TokenTransferRow;
+use crate::models::transaction::TransactionRow;
+use crate::services::batch_writer::BatchWriter;
+use crate::services::indexer::{BlockHeader, ChainIndexer};
 use crate::services::loader::LoaderTron;
+use crate::services::metrics;
 use crate::services::progress::{
     save_contract_call,
     save_money_flow,
-    save_sync_state,
     save_token_transfer,
     save_tx,
     save_wallet,
 };
+use crate::services::sync_status::SyncStatus;
 
 const TRC20_TRANSFER_TOPIC: &str =
     "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
@@ -44,26 +51,31 @@ async fn process_tx(
 
     // ---------- transaction ----------
     save_tx(
-        loader.clickhouse.clone(),
-        tx_id.clone(),
-        block_number,
-        owner.clone(),
-        to.clone(),
-        call_value.to_string(),
-        0,
+        &loader.writer,
+        TransactionRow {
+            hash: tx_id.clone(),
+            block_number,
+            from_addr: owner.clone(),
+            to_addr: to.clone(),
+            value: call_value.to_string(),
+            sensivity: 0,
+            category: None,
+            status: None,
+        },
     )
     .await?;
 
     // ---------- native TRX flow ----------
     if call_value > 0 && !to.is_empty() {
         save_money_flow(
-            loader.clickhouse.clone(),
+            &loader.writer,
             MoneyFlowRow {
                 tx_hash: tx_id.clone(),
                 from_addr: owner.clone(),
                 to_addr: to.clone(),
                 amount: call_value.to_string(),
                 asset: "TRX".to_string(),
+                cross_chain_exit: false,
             },
         )
         .await?;
@@ -91,7 +103,7 @@ async fn process_tx(
                 let amount = log["data"].as_str().unwrap_or("0").to_string();
 
                 save_token_transfer(
-                    loader.clickhouse.clone(),
+                    &loader.writer,
                     TokenTransferRow {
                         tx_hash: tx_id.clone(),
                         block_number,
@@ -105,13 +117,14 @@ async fn process_tx(
                 .await?;
 
                 save_money_flow(
-                    loader.clickhouse.clone(),
+                    &loader.writer,
                     MoneyFlowRow {
                         tx_hash: tx_id.clone(),
                         from_addr,
                         to_addr,
                         amount,
                         asset: token_address,
+                        cross_chain_exit: false,
                     },
                 )
                 .await?;
@@ -127,7 +140,7 @@ async fn process_tx(
         let method = data.get(0..8).unwrap_or("unknown").to_string();
 
         save_contract_call(
-            loader.clickhouse.clone(),
+            &loader.writer,
             ContractCallRow {
                 tx_hash: tx_id.clone(),
                 contract_address,
@@ -140,7 +153,7 @@ async fn process_tx(
 
     // ---------- wallets ----------
     save_wallet(
-        loader.clickhouse.clone(),
+        &loader.writer,
         &owner,
         "0".to_string(),
         0,
@@ -150,7 +163,7 @@ async fn process_tx(
 
     if !to.is_empty() {
         save_wallet(
-            loader.clickhouse.clone(),
+            &loader.writer,
             &to,
             "0".to_string(),
             0,
@@ -162,63 +175,70 @@ async fn process_tx(
     Ok(())
 }
 
-/// -----------------------------------------------------
-/// BLOCK LOOP
-/// -----------------------------------------------------
-pub async fn fetch_tron(
-    loader: Arc<LoaderTron>,
-    start_block: u64,
-    total_tron_txs: Option<u64>,
-) -> Result<()> {
-    let latest_block = loader.tron_client.get_block_number().await?;
-    let mut current_block = start_block;
-    let mut processed: u64 = 0;
-
-    while current_block <= latest_block {
-        let block = loader
-            .tron_client
-            .get_block_by_number(current_block)
-            .await?;
-
-        let empty: Vec<Value> = Vec::new();
-        let txs = block["transactions"].as_array().unwrap_or(&empty);
-
-        let mut tasks = FuturesUnordered::new();
-
-        for tx in txs {
-            if let Some(limit) = total_tron_txs {
-                if processed >= limit {
-                    break;
-                }
-            }
-            processed += 1;
-
-            let tx_owned = tx.clone();
-            let loader_cloned = loader.clone();
-            let block_num = current_block;
-
-            tasks.push(tokio::spawn(async move {
-                let _permit =
-                    loader_cloned.rpc_limiter.acquire().await.unwrap();
-                process_tx(loader_cloned.clone(), tx_owned, block_num).await
-            }));
-        
-            println!("[Tron] --> Queued tx #{}", processed);
-        }
+impl ChainIndexer for LoaderTron {
+    type Block = Value;
+    type Tx = Value;
+    type TxOutput = ();
 
-        while let Some(res) = tasks.next().await {
-            res??;
-        }
+    fn chain(&self) -> &str {
+        "tron"
+    }
 
-        save_sync_state(
-            loader.clickhouse.clone(),
-            "tron",
-            current_block,
-        )
-        .await?;
+    fn clickhouse(&self) -> &Arc<Client> {
+        &self.clickhouse
+    }
 
-        current_block += 1;
+    fn writer(&self) -> &Arc<BatchWriter> {
+        &self.writer
     }
 
-    Ok(())
+    fn sync_status(&self) -> &Arc<SyncStatus> {
+        &self.sync_status
+    }
+
+    async fn init_db(&self, admin: &Client) -> Result<()> {
+        init_tron_db(admin, &self.database).await?;
+
+        // Check Error Connect to clickhouse
+        println!("Passed clickhouse!!");
+        Ok(())
+    }
+
+    async fn head(&self) -> Result<u64> {
+        self.tron_client.get_block_number().await
+    }
+
+    async fn confirmed_head(&self, head: u64) -> Result<u64> {
+        Ok(self.finality.confirmed_head(head))
+    }
+
+    async fn fetch_block(&self, number: u64) -> Result<Option<Value>> {
+        Ok(Some(self.tron_client.get_block_by_number(number).await?))
+    }
+
+    fn header(&self, block: &Value) -> BlockHeader {
+        BlockHeader {
+            hash: block["blockID"].as_str().unwrap_or("").to_string(),
+            parent_hash: block["block_header"]["raw_data"]["parentHash"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            // block timestamps on Tron are in milliseconds
+            time: block["block_header"]["raw_data"]["timestamp"]
+                .as_i64()
+                .and_then(DateTime::from_timestamp_millis),
+        }
+    }
+
+    fn block_txs(&self, mut block: Value) -> Vec<Value> {
+        match block["transactions"].take() {
+            Value::Array(txs) => txs,
+            _ => Vec::new(),
+        }
+    }
+
+    async fn process_tx(self: Arc<Self>, tx: Value, block_number: u64) -> Result<()> {
+        let _permit = metrics::acquire("tron", &self.rpc_limiter).await?;
+        process_tx(self.clone(), tx, block_number).await
+    }
 }
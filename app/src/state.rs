@@ -1,11 +1,56 @@
 use clickhouse::Client;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::config::{AppConfig, ChainConfig};
+use crate::services::sync_status::SyncStatus;
+
 #[derive(Clone)]
 pub struct AppState {
-    pub clickhouse: Arc<Client>,
+    // chain name ("eth", "btc", "polygon", ...) -> client bound to that chain's database
+    pub clickhouse: Arc<HashMap<String, Arc<Client>>>,
+    // every configured chain, by name
+    pub chains: Arc<HashMap<String, ChainConfig>>,
+    // progress reported by the fetch loops running in this process
+    pub sync_status: Arc<SyncStatus>,
+    pub health_max_lag_blocks: u64,
 }
 
 impl AppState {
-    pub fn new(client: Client) -> Self { Self { clickhouse: Arc::new(client) } }
+    pub fn new(config: &AppConfig) -> Self {
+        let clickhouse = config
+            .chains
+            .iter()
+            .map(|chain| {
+                let client = Client::default()
+                    .with_url(&config.clickhouse_url)
+                    .with_user(&config.clickhouse_user)
+                    .with_password(&config.clickhouse_pass)
+                    .with_database(&chain.database);
+
+                (chain.name.clone(), Arc::new(client))
+            })
+            .collect();
+
+        let chains = config
+            .chains
+            .iter()
+            .map(|chain| (chain.name.clone(), chain.clone()))
+            .collect();
+
+        Self {
+            clickhouse: Arc::new(clickhouse),
+            chains: Arc::new(chains),
+            sync_status: Arc::new(SyncStatus::new()),
+            health_max_lag_blocks: config.health_max_lag_blocks,
+        }
+    }
+
+    pub fn client(&self, chain: &str) -> Option<Arc<Client>> {
+        self.clickhouse.get(chain).cloned()
+    }
+
+    pub fn chain(&self, chain: &str) -> Option<&ChainConfig> {
+        self.chains.get(chain)
+    }
 }
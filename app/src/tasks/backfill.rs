@@ -0,0 +1,369 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use futures::future::try_join_all;
+use tokio::sync::Mutex;
+
+use crate::config::{AppConfig, ChainConfig};
+use crate::db::backfill::get_latest_backfill_plan;
+use crate::db::reorg::delete_block_range;
+use crate::models::BackfillRangeRow;
+use crate::services::{
+    indexer::ChainIndexer,
+    metrics,
+    progress::{save_backfill_range, save_sync_state},
+    shutdown,
+};
+use crate::tasks::fetch_loop::{commit_block, fetch_confirmed_block, process_block};
+use crate::tasks::repair::{block_ranges, find_incomplete_blocks};
+
+/// Checkpoints of the running plan, shared by its workers.
+struct Shards {
+    ranges: Vec<BackfillRangeRow>,
+    // indexes into `ranges` no worker has picked up yet
+    queue: VecDeque<usize>,
+    // highest block stored in sync_state by this run
+    committed: Option<u64>,
+}
+
+impl Shards {
+    /// Last block up to which every block of the plan is indexed. Ranges are ordered by
+    /// range_start; a gap between two of them ends the prefix, an overlap doesn't.
+    fn contiguous_end(&self) -> Option<u64> {
+        let mut end: Option<u64> = None;
+        for range in &self.ranges {
+            if end.is_some_and(|end| range.range_start > end + 1) {
+                break;
+            }
+
+            let reached = if range.is_done() {
+                Some(range.range_end)
+            } else {
+                range.next_block.checked_sub(1).filter(|last| *last >= range.range_start)
+            };
+            if let Some(reached) = reached {
+                end = Some(end.map_or(reached, |end| end.max(reached)));
+            }
+
+            if !range.is_done() {
+                break;
+            }
+        }
+        end
+    }
+}
+
+fn plan_shards(chain: &str, plan: u64, start: u64, end: u64, shard_size: u64) -> Vec<BackfillRangeRow> {
+    let mut ranges = vec![];
+    let mut range_start = start;
+
+    while range_start <= end {
+        let range_end = range_start.saturating_add(shard_size - 1).min(end);
+        ranges.push(BackfillRangeRow {
+            chain: chain.to_string(),
+            plan,
+            range_start,
+            range_end,
+            next_block: range_start,
+        });
+        // the last shard may end at u64::MAX
+        let Some(next) = range_end.checked_add(1) else {
+            break;
+        };
+        range_start = next;
+    }
+
+    ranges
+}
+
+/// Indexes `start..=end` as `backfill_shard_size` block ranges on `backfill_workers`
+/// concurrent workers. Every range keeps its own checkpoint in `backfill_ranges`, and
+/// sync_state only advances over the contiguous prefix of finished blocks.
+///
+/// An unfinished plan covering `start` is resumed instead of planning a new one.
+/// Returns the block the sequential loop continues from, None on shutdown.
+pub async fn run_sharded_backfill<I: ChainIndexer>(
+    indexer: &Arc<I>,
+    chain: &ChainConfig,
+    config: &AppConfig,
+    start: u64,
+    end: u64,
+) -> Result<Option<u64>> {
+    let name = indexer.chain();
+    let tag = name.to_uppercase();
+    let clickhouse = indexer.clickhouse();
+
+    let latest = get_latest_backfill_plan(clickhouse, name).await?;
+    let mut shards = Shards {
+        ranges: latest,
+        queue: VecDeque::new(),
+        committed: None,
+    };
+
+    let resumable = shards.ranges.iter().any(|range| !range.is_done())
+        && shards.ranges.first().is_some_and(|first| first.range_start <= start)
+        && shards.contiguous_end().map_or(start == shards.ranges[0].range_start, |done| start <= done + 1);
+
+    if resumable {
+        println!(
+            "[{}] resuming backfill plan {} ({} of {} shards left)",
+            tag,
+            shards.ranges[0].plan,
+            shards.ranges.iter().filter(|range| !range.is_done()).count(),
+            shards.ranges.len()
+        );
+    } else {
+        // a single shard gains nothing over the sequential loop
+        if end < start || end - start < config.backfill_shard_size {
+            return Ok(Some(start));
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let plan = shards.ranges.first().map_or(now, |last| now.max(last.plan + 1));
+
+        shards.ranges = plan_shards(name, plan, start, end, config.backfill_shard_size);
+        for range in &shards.ranges {
+            save_backfill_range(clickhouse, range).await?;
+        }
+
+        println!(
+            "[{}] backfill plan {}: blocks {}..={} in {} shards on {} workers",
+            tag,
+            plan,
+            start,
+            end,
+            shards.ranges.len(),
+            config.backfill_workers
+        );
+    }
+
+    shards.queue = (0..shards.ranges.len())
+        .filter(|&i| !shards.ranges[i].is_done())
+        .collect();
+    let plan_end = shards.ranges.last().map_or(end, |range| range.range_end);
+
+    let shards = Mutex::new(shards);
+    let workers = (0..config.backfill_workers).map(|worker| run_worker(indexer, &shards, worker, chain.is_evm()));
+    try_join_all(workers).await?;
+
+    if shutdown::is_requested() {
+        return Ok(None);
+    }
+
+    println!("[{}] backfill plan finished at block {}", tag, plan_end);
+    Ok(Some(plan_end + 1))
+}
+
+/// Saves the shard's checkpoint and moves sync_state over the contiguous prefix. The
+/// blocks before `range.next_block` are already flushed by `commit_block`.
+async fn save_checkpoint<I: ChainIndexer>(
+    indexer: &Arc<I>,
+    shards: &Mutex<Shards>,
+    range: &BackfillRangeRow,
+) -> Result<()> {
+    save_backfill_range(indexer.clickhouse(), range).await?;
+
+    let mut shards = shards.lock().await;
+    // sync_state only moves over blocks with no unfinished block below them
+    if let Some(contiguous) = shards.contiguous_end()
+        && shards.committed.is_none_or(|committed| committed < contiguous)
+    {
+        save_sync_state(indexer.clickhouse().clone(), indexer.chain(), contiguous).await?;
+        shards.committed = Some(contiguous);
+    }
+    Ok(())
+}
+
+/// Takes shards off the queue until it is empty or shutdown is requested.
+///
+/// Checkpoints are saved once per writer flush interval rather than per block, so after a
+/// crash a shard may resume below blocks it already committed; those are recognised by
+/// their `blocks` record and skipped instead of being indexed twice. Blocks without a
+/// complete record may still hold rows flushed before the crash, so whatever is stored
+/// for them is deleted before they are indexed again.
+async fn run_worker<I: ChainIndexer>(
+    indexer: &Arc<I>,
+    shards: &Mutex<Shards>,
+    worker: usize,
+    evm: bool,
+) -> Result<()> {
+    let name = indexer.chain();
+    let tag = name.to_uppercase();
+    let interval = indexer.writer().flush_interval();
+
+    loop {
+        let Some((index, mut range)) = ({
+            let mut shards = shards.lock().await;
+            shards
+                .queue
+                .pop_front()
+                .map(|index| (index, shards.ranges[index].clone()))
+        }) else {
+            return Ok(());
+        };
+
+        println!(
+            "[{}] worker {} took shard {}..={} at block {}",
+            tag, worker, range.range_start, range.range_end, range.next_block
+        );
+
+        // every block of a plan is indexed by this code, which records what it commits
+        let incomplete = find_incomplete_blocks(
+            indexer.clickhouse(),
+            range.next_block,
+            range.range_end,
+            range.range_start,
+        )
+        .await?;
+        // partial rows would otherwise be duplicated and double-count balances
+        for (from_block, to_block) in block_ranges(&incomplete) {
+            delete_block_range(indexer.clickhouse(), from_block, to_block, evm).await?;
+        }
+        let incomplete: HashSet<u64> = incomplete.into_iter().collect();
+        let mut saved_at = Instant::now();
+
+        while !range.is_done() {
+            // stop between blocks so the checkpoint always points at a fully processed block
+            if shutdown::is_requested() {
+                return save_checkpoint(indexer, shards, &range).await;
+            }
+
+            let block_number = range.next_block;
+            let mut block_txs = 0;
+            let mut block_time = None;
+
+            if incomplete.contains(&block_number) {
+                let Some(block) = fetch_confirmed_block(indexer.as_ref(), block_number).await? else {
+                    return save_checkpoint(indexer, shards, &range).await;
+                };
+
+                let header = indexer.header(&block);
+                let txs = indexer.block_txs(block);
+                block_txs = process_block(indexer, block_number, txs, None).await?;
+
+                commit_block(indexer.as_ref(), block_number, &header, block_txs).await?;
+                block_time = header.time;
+            }
+
+            range.next_block += 1;
+            shards.lock().await.ranges[index].next_block = range.next_block;
+
+            if range.is_done() || saved_at.elapsed() >= interval {
+                save_checkpoint(indexer, shards, &range).await?;
+                saved_at = Instant::now();
+            }
+
+            // status reports the contiguous block, throughput counts every block
+            match shards.lock().await.committed {
+                Some(committed) => {
+                    let time = if committed == block_number { block_time } else { None };
+                    indexer.sync_status().record_block(name, committed, time, block_txs);
+                }
+                None => metrics::record_block(name, block_txs),
+            }
+        }
+
+        println!(
+            "[{}] worker {} finished shard {}..={}",
+            tag, worker, range.range_start, range.range_end
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(range_start: u64, range_end: u64, next_block: u64) -> BackfillRangeRow {
+        BackfillRangeRow {
+            chain: "eth".into(),
+            plan: 1,
+            range_start,
+            range_end,
+            next_block,
+        }
+    }
+
+    fn shards(ranges: Vec<BackfillRangeRow>) -> Shards {
+        Shards {
+            ranges,
+            queue: VecDeque::new(),
+            committed: None,
+        }
+    }
+
+    fn bounds(ranges: &[BackfillRangeRow]) -> Vec<(u64, u64)> {
+        ranges.iter().map(|r| (r.range_start, r.range_end)).collect()
+    }
+
+    #[test]
+    fn plan_splits_unevenly_with_a_short_last_shard() {
+        let ranges = plan_shards("eth", 7, 100, 124, 10);
+        assert_eq!(bounds(&ranges), vec![(100, 109), (110, 119), (120, 124)]);
+        assert!(ranges.iter().all(|r| r.next_block == r.range_start && r.plan == 7));
+    }
+
+    #[test]
+    fn plan_of_a_single_block() {
+        let ranges = plan_shards("eth", 1, 42, 42, 10);
+        assert_eq!(bounds(&ranges), vec![(42, 42)]);
+        assert!(!ranges[0].is_done());
+    }
+
+    #[test]
+    fn plan_is_empty_when_end_is_below_start() {
+        assert!(plan_shards("eth", 1, 10, 9, 10).is_empty());
+    }
+
+    #[test]
+    fn plan_reaching_u64_max_terminates() {
+        let ranges = plan_shards("eth", 1, u64::MAX - 5, u64::MAX, 4);
+        assert_eq!(
+            bounds(&ranges),
+            vec![(u64::MAX - 5, u64::MAX - 2), (u64::MAX - 1, u64::MAX)]
+        );
+    }
+
+    #[test]
+    fn contiguous_end_of_a_fresh_plan_is_none() {
+        assert_eq!(shards(plan_shards("eth", 1, 0, 29, 10)).contiguous_end(), None);
+    }
+
+    #[test]
+    fn contiguous_end_stops_at_the_first_unfinished_shard() {
+        // later shards being further along doesn't count
+        let shards = shards(vec![range(0, 9, 10), range(10, 19, 15), range(20, 29, 30)]);
+        assert_eq!(shards.contiguous_end(), Some(14));
+    }
+
+    #[test]
+    fn contiguous_end_resuming_from_partially_done_ranges() {
+        let untouched_second = shards(vec![range(0, 9, 10), range(10, 19, 10)]);
+        assert_eq!(untouched_second.contiguous_end(), Some(9));
+
+        let partial_first = shards(vec![range(0, 9, 4), range(10, 19, 20)]);
+        assert_eq!(partial_first.contiguous_end(), Some(3));
+
+        let all_done = shards(vec![range(0, 9, 10), range(10, 19, 20)]);
+        assert_eq!(all_done.contiguous_end(), Some(19));
+    }
+
+    #[test]
+    fn contiguous_end_with_overlapping_checkpoints() {
+        // a longer done range covering the next one keeps its own end
+        let covering = shards(vec![range(0, 19, 20), range(10, 14, 15), range(15, 29, 25)]);
+        assert_eq!(covering.contiguous_end(), Some(24));
+
+        // an unfinished range inside a done one still stops the prefix at its progress
+        let inside = shards(vec![range(0, 19, 20), range(5, 24, 12)]);
+        assert_eq!(inside.contiguous_end(), Some(19));
+    }
+
+    #[test]
+    fn contiguous_end_stops_at_a_gap_between_ranges() {
+        let shards = shards(vec![range(0, 9, 10), range(20, 29, 30)]);
+        assert_eq!(shards.contiguous_end(), Some(9));
+    }
+}
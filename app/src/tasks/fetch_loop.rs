@@ -1,122 +1,161 @@
 use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
 use clickhouse::Client;
+use futures::stream::{FuturesUnordered, StreamExt};
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, ChainConfig, ChainKind, SyncMode};
 
+use crate::db::blocks::get_block_hash;
+use crate::db::sync_state::get_last_synced_block;
+use crate::models::block::BlockRow;
 use crate::services::{
-    loader::{LoaderEth, LoaderBtc, LoaderBsc, LoaderTron},
-    bitcoin,
-    ethereum,
-    bsc,
-    tron,
-    sync_logic::{resolve_start_block_btc, resolve_start_block_evm, resolve_start_block_tron},
+    indexer::{BlockHeader, ChainIndexer},
+    loader::{LoaderBtc, LoaderEvm, LoaderTron},
+    progress::{save_block, save_sync_state},
+    reorg,
+    shutdown,
+    sync_logic::resolve_start_block,
+    sync_status::{HeadProbe, SyncStatus},
 };
-
-use crate::db::init_eth::init_eth_db;
-use crate::db::init_btc::init_btc_db;
-use crate::db::init_bsc::init_bsc_db;
-use crate::db::init_tron::init_tron_db;
-
-use crate::db::sync_state::get_last_synced_block;
-
-pub async fn run_btc_loop(config: AppConfig) -> Result<()> {
-    println!("===============================");
-    println!("[BTC] Starting BTC fetch loop...");
-    println!("===============================");
-
-    // Loader (client با دیتابیس btc_db)
-    let loader = Arc::new(LoaderBtc::new(&config).await?);
-
-    // client موقت بدون database برای init
-    let admin_client = Client::default()
-        .with_url(&config.clickhouse_url)
-        .with_user(&config.clickhouse_user)
-        .with_password(&config.clickhouse_pass);
-
-    // init دیتابیس و جدول‌ها
-    init_btc_db(&admin_client).await?;
-
-    // گرفتن آخرین بلاک sync شده از دیتابیس
-    let last_synced = get_last_synced_block(&loader.clickhouse, "btc").await?;
-
-    // تعیین start_block با توجه به sync_mode
-    let start_block =
-        resolve_start_block_btc(&config.sync_mode, config.btc_start_block, last_synced);
-
-    println!(
-        "[BTC] sync_mode={:?} start_block={} last_synced={:?}",
-        config.sync_mode, start_block, last_synced
-    );
-
-    // شروع fetch
-    bitcoin::fetch_btc(
-        loader.clone(),
-        start_block,
-        config.total_btc_txs,
-        config.btc_api_url
-            .as_ref()
-            .expect("BTC_API_URL is not set!"),
+use crate::tasks::{backfill, repair};
+
+// a confirmed block the node doesn't return is asked for again this many times, with a
+// doubling delay, before the loop fails; it is never skipped
+const MISSING_BLOCK_RETRIES: u32 = 5;
+const MISSING_BLOCK_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Fetches a block at or below the confirmed head. None only when shutdown was requested
+/// while waiting for it.
+pub(crate) async fn fetch_confirmed_block<I: ChainIndexer>(
+    indexer: &I,
+    block_number: u64,
+) -> Result<Option<I::Block>> {
+    let tag = indexer.chain().to_uppercase();
+    let mut delay = MISSING_BLOCK_BACKOFF;
+
+    for retry in 1..=MISSING_BLOCK_RETRIES + 1 {
+        if let Some(block) = indexer.fetch_block(block_number).await? {
+            return Ok(Some(block));
+        }
+        if retry > MISSING_BLOCK_RETRIES {
+            break;
+        }
+
+        println!(
+            "[{}] block {} not served, retry {} of {} in {:?}",
+            tag, block_number, retry, MISSING_BLOCK_RETRIES, delay
+        );
+        if !shutdown::sleep(delay).await {
+            return Ok(None);
+        }
+        delay *= 2;
+    }
+
+    anyhow::bail!(
+        "[{}] block {} not served after {} retries",
+        tag,
+        block_number,
+        MISSING_BLOCK_RETRIES
     )
-    .await?;
-
-    println!("[BTC] Finished successfully.");
-    Ok(())
 }
 
-pub async fn run_eth_loop(config: AppConfig) -> Result<()> {
-    println!("===============================");
-    println!("[ETH] Starting ETH fetch loop...");
-    println!("===============================");
+/// Reads the head, reports head and confirmed head, and returns the confirmed head.
+async fn poll_heads<I: ChainIndexer>(indexer: &I) -> Result<u64> {
+    let chain = indexer.chain();
 
-    // Loader (client با دیتابیس eth_db)
-    let loader = Arc::new(LoaderEth::new(&config).await?);
+    let head = indexer.head().await?;
+    indexer.sync_status().set_head(chain, head);
 
-    // client موقت بدون database برای init
-    let admin_client = Client::default()
-        .with_url(&config.clickhouse_url)
-        .with_user(&config.clickhouse_user)
-        .with_password(&config.clickhouse_pass);
-
-    // init دیتابیس و جدول‌ها
-    init_eth_db(&admin_client).await?;
-
-    // گرفتن آخرین بلاک sync شده از دیتابیس
-    let last_synced = get_last_synced_block(&loader.clickhouse, "eth").await?;
-
-    // تعیین start_block با توجه به sync_mode
-    let start_block = resolve_start_block_evm(
-        &config.sync_mode,
-        loader.eth_provider.clone(),
-        config.eth_start_block,
-        last_synced,
-    )
-    .await?;
+    let confirmed = indexer.confirmed_head(head).await?;
+    indexer.sync_status().set_confirmed_head(chain, confirmed);
+    Ok(confirmed)
+}
 
-    println!(
-        "[ETH] sync_mode={:?} start_block={} last_synced={:?}",
-        config.sync_mode, start_block, last_synced
-    );
+/// Runs the txs of one block concurrently (at most `limit` of them), then `after_block`.
+/// Returns how many ran.
+pub(crate) async fn process_block<I: ChainIndexer>(
+    indexer: &Arc<I>,
+    block_number: u64,
+    txs: Vec<I::Tx>,
+    limit: Option<u64>,
+) -> Result<u64> {
+    let mut tasks = FuturesUnordered::new();
+    let mut queued: u64 = 0;
+
+    for tx in txs {
+        if limit.is_some_and(|limit| queued >= limit) {
+            break;
+        }
+
+        tasks.push(tokio::spawn(indexer.clone().process_tx(tx, block_number)));
+
+        queued += 1;
+    }
+
+    let mut outputs = Vec::with_capacity(tasks.len());
+    while let Some(res) = tasks.next().await {
+        outputs.push(res??);
+    }
+
+    indexer.after_block(outputs).await?;
+    Ok(queued)
+}
 
-    // شروع fetch
-    ethereum::fetch_eth(
-        loader.clone(),
-        start_block,
-        config.total_eth_txs,
+/// Flushes every row written for a fully processed block together with its `blocks` record
+/// (hash for reorg checks, tx count for gap repair). Afterwards the block may be marked as synced.
+pub(crate) async fn commit_block<I: ChainIndexer>(
+    indexer: &I,
+    block_number: u64,
+    header: &BlockHeader,
+    tx_count: u64,
+) -> Result<()> {
+    let writer = indexer.writer();
+
+    // buffered last: the record is only inserted once the block's rows are
+    writer.flush().await?;
+    save_block(
+        writer,
+        BlockRow {
+            number: block_number,
+            hash: header.hash.clone(),
+            parent_hash: header.parent_hash.clone(),
+            timestamp: header.time.map_or(0, |t| t.timestamp() as u64),
+            tx_count: Some(tx_count),
+        },
     )
     .await?;
-
-    println!("[ETH] Finished successfully.");
-    Ok(())
+    writer.flush().await
 }
 
-pub async fn run_bsc_loop(config: AppConfig) -> Result<()> {
+/// Generic block loop shared by every chain: init, start-block resolution, then blocks
+/// from the start block up to the confirmed head (and onwards in follow mode), until the
+/// tx cap is hit or shutdown is requested.
+pub async fn run_indexer<I: ChainIndexer>(
+    indexer: Arc<I>,
+    chain: &ChainConfig,
+    config: &AppConfig,
+) -> Result<()> {
+    let name = indexer.chain();
+    let tag = name.to_uppercase();
+    let clickhouse = indexer.clickhouse().clone();
+    let writer = indexer.writer().clone();
+    let total_txs = chain.total_txs;
+    let follow = config.follow_interval(chain);
+
     println!("===============================");
-    println!("[BSC] Starting BSC fetch loop...");
+    println!("[{}] Starting {} fetch loop...", tag, tag);
     println!("===============================");
 
-    // Loader (client با دیتابیس bsc_db)
-    let loader = Arc::new(LoaderBsc::new(&config).await?);
+    let probe_indexer = indexer.clone();
+    let probe: HeadProbe = Arc::new(move || {
+        let indexer = probe_indexer.clone();
+        Box::pin(async move { indexer.head().await })
+    });
+    indexer
+        .sync_status()
+        .register(name, config.sync_mode.clone(), probe);
 
     // client موقت بدون database برای init
     let admin_client = Client::default()
@@ -125,78 +164,167 @@ pub async fn run_bsc_loop(config: AppConfig) -> Result<()> {
         .with_password(&config.clickhouse_pass);
 
     // init دیتابیس و جدول‌ها
-    init_bsc_db(&admin_client).await?;
+    indexer.init_db(&admin_client).await?;
+
+    // only blocks at or below the confirmed head are indexed
+    let mut latest_block = poll_heads(indexer.as_ref()).await?;
 
     // گرفتن آخرین بلاک sync شده از دیتابیس
-    let last_synced = get_last_synced_block(&loader.clickhouse, "bsc").await?;
+    let last_synced = get_last_synced_block(&clickhouse, name).await?;
+
+    if matches!(config.sync_mode, SyncMode::Repair) {
+        repair::run_repair(&indexer, chain, last_synced).await?;
+        println!("[{}] Finished successfully.", tag);
+        return Ok(());
+    }
 
     // تعیین start_block با توجه به sync_mode
-    let start_block = resolve_start_block_evm(
+    let start_block = resolve_start_block(
         &config.sync_mode,
-        loader.bsc_provider.clone(),
-        config.bsc_start_block,
+        chain.start_block,
         last_synced,
-    )
-    .await?;
+        latest_block,
+    );
 
     println!(
-        "[BSC] sync_mode={:?} start_block={} last_synced={:?}",
-        config.sync_mode, start_block, last_synced
+        "[{}] sync_mode={:?} follow={} start_block={} confirmed_head={} last_synced={:?}",
+        tag, config.sync_mode, config.follow, start_block, latest_block, last_synced
     );
 
-    // شروع fetch
-    bsc::fetch_bsc(
-        loader.clone(),
-        start_block,
-        config.total_bsc_txs,
-    )
-    .await?;
-
-    println!("[BSC] Finished successfully.");
+    let mut current_block = start_block;
+
+    // the historical part runs on concurrent shard workers when configured
+    if config.backfill_workers > 1 && total_txs.is_none() && !matches!(config.sync_mode, SyncMode::Live) {
+        match backfill::run_sharded_backfill(&indexer, chain, config, start_block, latest_block).await? {
+            Some(next_block) => current_block = next_block,
+            None => {
+                println!("[{}] shutdown requested, stopping backfill", tag);
+                return Ok(());
+            }
+        }
+    }
+
+    let reorg_max_depth = indexer.reorg_max_depth();
+    let mut tx_count: u64 = 0;
+
+    // hash the next block's parent_hash must match; None skips the check
+    let mut expected_parent = match (reorg_max_depth, current_block.checked_sub(1)) {
+        (Some(_), Some(prev)) => get_block_hash(&clickhouse, prev).await?,
+        _ => None,
+    };
+
+    loop {
+        if total_txs.is_some_and(|cap| tx_count >= cap) {
+            break;
+        }
+
+        // stop between blocks so sync_state always points at a fully processed block
+        if shutdown::is_requested() {
+            println!("[{}] shutdown requested, stopping before block {}", tag, current_block);
+            break;
+        }
+
+        // caught up with the head: stop, or wait for the next block in follow mode
+        if current_block > latest_block {
+            let Some(interval) = follow else {
+                break;
+            };
+
+            // the shutdown check at the top of the loop handles an interrupted sleep
+            if !shutdown::sleep(interval).await {
+                continue;
+            }
+            latest_block = poll_heads(indexer.as_ref()).await?;
+            continue;
+        }
+
+        // near the head a lagging node may not serve the block yet; follow mode waits for it
+        // like for a new head, otherwise it is retried with backoff. Never skipped.
+        let block = match follow {
+            Some(interval) => match indexer.fetch_block(current_block).await? {
+                Some(block) => block,
+                None => {
+                    shutdown::sleep(interval).await;
+                    continue;
+                }
+            },
+            None => match fetch_confirmed_block(indexer.as_ref(), current_block).await? {
+                Some(block) => block,
+                // shutdown, handled at the top of the loop
+                None => continue,
+            },
+        };
+
+        let header = indexer.header(&block);
+
+        // the block has to build on the one stored before it, otherwise the chain reorganized
+        if let (Some(max_depth), Some(expected)) = (reorg_max_depth, &expected_parent)
+            && *expected != header.parent_hash
+        {
+            current_block =
+                reorg::rollback_to_fork_point(indexer.as_ref(), current_block, max_depth).await?;
+            expected_parent =
+                get_block_hash(&clickhouse, current_block.saturating_sub(1)).await?;
+            continue;
+        }
+
+        let txs = indexer.block_txs(block);
+        let block_tx_total = txs.len() as u64;
+
+        let limit = total_txs.map(|cap| cap.saturating_sub(tx_count));
+        let block_txs = process_block(&indexer, current_block, txs, limit).await?;
+        tx_count += block_txs;
+
+        let fully_processed_block = block_txs == block_tx_total;
+
+        // فقط اگر بلاک کامل پردازش شد sync_state رو آپدیت کن
+        if !fully_processed_block {
+            // the processed part stays stored, as without buffering; sync_state doesn't move
+            writer.flush().await?;
+            println!(
+                "[{}] stopped mid-block {} (tx limit reached) | total tx processed {}",
+                tag, current_block, tx_count
+            );
+            break;
+        }
+
+        // every row of the block has to be stored before sync_state moves past it
+        commit_block(indexer.as_ref(), current_block, &header, block_txs).await?;
+        if reorg_max_depth.is_some() {
+            expected_parent = Some(header.hash);
+        }
+        save_sync_state(clickhouse.clone(), name, current_block).await?;
+        indexer.sync_status().record_block(
+            name,
+            current_block,
+            header.time,
+            block_txs,
+        );
+
+        current_block += 1;
+    }
+
+    println!("[{}] Finished successfully.", tag);
     Ok(())
 }
 
-
-pub async fn run_tron_loop(config: AppConfig) -> Result<()> {
-    println!("===============================");
-    println!("[TRON] Starting TRON fetch loop...");
-    println!("===============================");
-
-    let loader = Arc::new(LoaderTron::new(&config).await?);
-
-    let admin_client = Client::default()
-        .with_url(&config.clickhouse_url)
-        .with_user(&config.clickhouse_user)
-        .with_password(&config.clickhouse_pass);
-
-    init_tron_db(&admin_client).await?;
-    
-    // Check Error Connect to clickhouse
-    println!("Passed clickhouse!!");
-
-    let last_synced =
-        get_last_synced_block(&loader.clickhouse, "tron").await?;
-
-    let start_block = resolve_start_block_tron(
-        &config.sync_mode,
-        loader.tron_client.clone(),
-        config.tron_start_block,
-        last_synced,
-    )
-    .await?;
-
-    println!(
-        "[TRON] sync_mode={:?} start_block={} last_synced={:?}",
-        config.sync_mode, start_block, last_synced
-    );
-
-    tron::fetch_tron(
-        loader.clone(),
-        start_block,
-        Some(config.total_tron_txs),
-    )
-    .await?;
-
-    println!("[TRON] Finished successfully.");
-    Ok(())
-}
\ No newline at end of file
+pub async fn run_chain_loop(
+    chain: ChainConfig,
+    config: AppConfig,
+    sync_status: Arc<SyncStatus>,
+) -> Result<()> {
+    match chain.kind {
+        ChainKind::Btc => {
+            let loader = LoaderBtc::new(&config, &chain, sync_status).await?;
+            run_indexer(Arc::new(loader), &chain, &config).await
+        }
+        ChainKind::Evm(_) => {
+            let loader = LoaderEvm::new(&config, &chain, sync_status).await?;
+            run_indexer(Arc::new(loader), &chain, &config).await
+        }
+        ChainKind::Tron => {
+            let loader = LoaderTron::new(&config, &chain, sync_status).await?;
+            run_indexer(Arc::new(loader), &chain, &config).await
+        }
+    }
+}
@@ -1 +1,4 @@
+pub mod backfill;
 pub mod fetch_loop;
+pub mod repair;
+pub mod supervisor;
\ No newline at end of file
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use anyhow::Result;
+use clickhouse::Client;
+
+use crate::config::ChainConfig;
+use crate::db::blocks::{get_first_recorded_block, get_recorded_tx_counts, get_stored_tx_counts};
+use crate::db::reorg::delete_block_range;
+use crate::services::{indexer::ChainIndexer, shutdown};
+use crate::tasks::fetch_loop::{commit_block, fetch_confirmed_block, process_block};
+
+// blocks checked per scan query
+const SCAN_CHUNK: u64 = 10_000;
+
+/// Blocks in `from_block..=to_block` that are not provably complete: no `blocks` record
+/// (never committed: a block the tx cap stopped in, a failed task, a crash between
+/// inserts) or a recorded tx count that differs from the transactions stored for it.
+///
+/// Blocks below `unrecorded_from` may have been indexed before records were kept, so a
+/// missing record only counts from there on.
+pub(crate) async fn find_incomplete_blocks(
+    clickhouse: &Client,
+    from_block: u64,
+    to_block: u64,
+    unrecorded_from: u64,
+) -> Result<Vec<u64>> {
+    let recorded = get_recorded_tx_counts(clickhouse, from_block, to_block).await?;
+    let stored = get_stored_tx_counts(clickhouse, from_block, to_block).await?;
+
+    Ok(incomplete_blocks(from_block..=to_block, unrecorded_from, &recorded, &stored))
+}
+
+fn incomplete_blocks(
+    blocks: RangeInclusive<u64>,
+    unrecorded_from: u64,
+    recorded: &HashMap<u64, Option<u64>>,
+    stored: &HashMap<u64, u64>,
+) -> Vec<u64> {
+    blocks
+        .filter(|number| match recorded.get(number) {
+            None => *number >= unrecorded_from,
+            // records from before tx counts were kept only prove the block was committed
+            Some(None) => false,
+            Some(Some(tx_count)) => stored.get(number).copied().unwrap_or(0) != *tx_count,
+        })
+        .collect()
+}
+
+/// Consecutive block numbers merged into inclusive ranges.
+pub(crate) fn block_ranges(blocks: &[u64]) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = vec![];
+
+    for &number in blocks {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == number => *end = number,
+            _ => ranges.push((number, number)),
+        }
+    }
+
+    ranges
+}
+
+/// Repair mode: scans `start_block..=last_synced` for incomplete blocks, deletes whatever
+/// was stored for them and indexes them again. Runs once, sync_state is left untouched.
+pub async fn run_repair<I: ChainIndexer>(
+    indexer: &Arc<I>,
+    chain: &ChainConfig,
+    last_synced: Option<u64>,
+) -> Result<()> {
+    let tag = indexer.chain().to_uppercase();
+    let clickhouse = indexer.clickhouse();
+
+    let Some(last_synced) = last_synced.filter(|last| *last >= chain.start_block) else {
+        println!("[{}] repair: nothing indexed since start_block {}", tag, chain.start_block);
+        return Ok(());
+    };
+
+    println!(
+        "[{}] repair: scanning blocks {}..={}",
+        tag, chain.start_block, last_synced
+    );
+
+    // older blocks without a record predate the `blocks` table, not a failed insert
+    let Some(first_recorded) = get_first_recorded_block(clickhouse).await? else {
+        println!("[{}] repair: no block records yet, nothing to check", tag);
+        return Ok(());
+    };
+    if first_recorded > chain.start_block {
+        println!(
+            "[{}] repair: blocks below {} predate block records and are not checked",
+            tag, first_recorded
+        );
+    }
+
+    let mut repaired: u64 = 0;
+    let mut missing: u64 = 0;
+    let mut chunk_start = chain.start_block;
+
+    while chunk_start <= last_synced {
+        let chunk_end = chunk_start.saturating_add(SCAN_CHUNK - 1).min(last_synced);
+        let incomplete = find_incomplete_blocks(clickhouse, chunk_start, chunk_end, first_recorded).await?;
+
+        for (from_block, to_block) in block_ranges(&incomplete) {
+            println!("[{}] repair: re-indexing blocks {}..={}", tag, from_block, to_block);
+
+            for block_number in from_block..=to_block {
+                if shutdown::is_requested() {
+                    println!("[{}] shutdown requested, stopping repair", tag);
+                    return Ok(());
+                }
+
+                // what is stored for a block is only deleted once its replacement is at hand
+                let block = match fetch_confirmed_block(indexer.as_ref(), block_number).await {
+                    Ok(Some(block)) => block,
+                    // shutdown, handled above
+                    Ok(None) => continue,
+                    Err(err) => {
+                        println!("[{}] repair: {:#}, block left as it was", tag, err);
+                        missing += 1;
+                        continue;
+                    }
+                };
+
+                // stale partial rows would otherwise be duplicated or double-count balances
+                delete_block_range(clickhouse, block_number, block_number, chain.is_evm()).await?;
+
+                let header = indexer.header(&block);
+                let txs = indexer.block_txs(block);
+                let tx_count = process_block(indexer, block_number, txs, None).await?;
+                commit_block(indexer.as_ref(), block_number, &header, tx_count).await?;
+
+                repaired += 1;
+                println!("[{}] repair: block {} re-indexed ({} txs)", tag, block_number, tx_count);
+            }
+        }
+
+        chunk_start = chunk_end + 1;
+    }
+
+    println!(
+        "[{}] repair finished: {} block(s) re-indexed, {} not served by the node",
+        tag, repaired, missing
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unrecorded_blocks_before_the_first_record_are_left_alone() {
+        let recorded = HashMap::from([(105, Some(2)), (106, Some(1))]);
+        let stored = HashMap::from([(105, 2), (106, 1)]);
+
+        assert_eq!(incomplete_blocks(100..=108, 105, &recorded, &stored), vec![107, 108]);
+    }
+
+    #[test]
+    fn recorded_blocks_are_checked_against_stored_txs() {
+        let recorded = HashMap::from([(1, Some(2)), (2, Some(0)), (3, Some(3)), (4, None)]);
+        let stored = HashMap::from([(1, 2), (3, 1)]);
+
+        // 4 was recorded before tx counts were kept
+        assert_eq!(incomplete_blocks(1..=4, 0, &recorded, &stored), vec![3]);
+    }
+
+    #[test]
+    fn runs_of_blocks_become_ranges() {
+        assert_eq!(block_ranges(&[1, 2, 3, 7, 9, 10]), vec![(1, 3), (7, 7), (9, 10)]);
+        assert!(block_ranges(&[]).is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+
+use crate::config::{AppConfig, ChainConfig};
+use crate::services::shutdown;
+use crate::services::sync_status::SyncStatus;
+use crate::tasks::fetch_loop::run_chain_loop;
+
+/// Runs one chain's fetch loop in its own task and restarts it on error or panic
+/// according to the restart policy, so a failing chain never takes the others down.
+/// Returns when the loop finishes its work, the policy gives up or shutdown is requested.
+pub async fn supervise_chain(chain: ChainConfig, config: AppConfig, sync_status: Arc<SyncStatus>) {
+    let name = chain.name.as_str();
+    let tag = name.to_uppercase();
+    let max_backoff = Duration::from_secs(config.restart_max_backoff_seconds);
+    let mut failures: u32 = 0;
+
+    loop {
+        let started = Instant::now();
+        let task = tokio::spawn(run_chain_loop(
+            chain.clone(),
+            config.clone(),
+            sync_status.clone(),
+        ));
+
+        let err = match task.await {
+            Ok(Ok(())) => return,
+            Ok(Err(err)) => err,
+            Err(join_err) => anyhow!("fetch loop panicked: {}", join_err),
+        };
+        sync_status.record_error(name, &err);
+
+        if shutdown::is_requested() {
+            eprintln!("[{}] fetch loop failed during shutdown: {:#}", tag, err);
+            return;
+        }
+
+        // a loop that stayed up longer than the longest backoff counts as recovered
+        if started.elapsed() > max_backoff {
+            failures = 0;
+        }
+        failures += 1;
+
+        if let Some(max_retries) = config.restart_max_retries
+            && failures > max_retries
+        {
+            eprintln!(
+                "[{}] fetch loop failed: {:#}; giving up after {} restart(s)",
+                tag, err, max_retries
+            );
+            sync_status.mark_stopped(name);
+            return;
+        }
+
+        let delay = Duration::from_secs(
+            config
+                .restart_backoff_seconds
+                .saturating_mul(1u64 << (failures - 1).min(20)),
+        )
+        .min(max_backoff);
+
+        eprintln!(
+            "[{}] fetch loop failed: {:#}; restarting in {}s (attempt {})",
+            tag,
+            err,
+            delay.as_secs(),
+            failures
+        );
+        sync_status.record_restart(name);
+        if !shutdown::sleep(delay).await {
+            return;
+        }
+    }
+}